@@ -1,6 +1,9 @@
 use num::Signed;
 
+use crate::list;
+use crate::utils::list::List;
 use crate::utils::ops::*;
+use crate::utils::procedure::Procedure;
 use std::fmt::Debug;
 use std::ops::{Add, Div, Mul, Sub};
 use std::process::Output;
@@ -57,6 +60,20 @@ fn test_repeat() {
     assert_eq!(625, repeat(Box::new(square), 2)(5));
 }
 
+#[test]
+fn test_procedure_list() {
+    let procs = list!(
+        Procedure::from(compose(Box::new(square), Box::new(inc))),
+        Procedure::from(repeat(Box::new(inc), 3)),
+        Procedure::from(double(Box::new(square::<i32>)))
+    );
+    let results: Vec<i32> = procs
+        .iter_downcast::<Procedure<i32>>()
+        .map(|p| p.unwrap()(2))
+        .collect();
+    assert_eq!(results, [9, 5, 16]);
+}
+
 // ex1_44
 fn smooth<'a, T>(f: Box<dyn Fn(T) -> T>) -> Box<dyn Fn(T) -> T + 'a>
 where
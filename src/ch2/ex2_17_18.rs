@@ -19,7 +19,7 @@ fn last_pair<T: Default + Debug>(mut list: List) -> List {
 #[test]
 fn test_last_pair() {
     let l = list!(1, 2, 3, 4);
-    assert_eq!(4, last_pair::<i32>(l).car().unwrap());
+    assert_eq!(4, last_pair::<i32>(l).car::<i32>().unwrap());
 }
 
 fn reverse(list: List) -> List {
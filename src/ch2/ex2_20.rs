@@ -2,7 +2,8 @@ use std::mem::replace;
 
 use crate::{list, utils::cons::*, utils::list::*};
 
-// since rust won't support any kind of dotted-tail notation
+// dotted-tail data can be written as list!(1, 2; rest), but rust
+// functions have no dotted-tail parameters like (define (f x . rest)),
 // use rust macro for that achieve same grammar
 macro_rules! same_parity {
     ( $base:expr, $( $a:expr ),*) => {
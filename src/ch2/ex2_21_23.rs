@@ -4,7 +4,9 @@ use crate::{
 };
 
 fn square_list(l: List) -> List {
-    l.into_iter_downcast::<i32>().map(|x| square(x)).collect()
+    l.into_iter_downcast::<i32>()
+        .map(|x| square(x.unwrap()))
+        .collect()
 }
 
 #[test]
@@ -15,7 +17,9 @@ fn test_square_list() {
 }
 
 fn for_each(l: List, apply: impl FnMut(i32)) {
-    l.into_iter_downcast::<i32>().for_each(apply)
+    l.into_iter_downcast::<i32>()
+        .map(Result::unwrap)
+        .for_each(apply)
 }
 
 #[test]
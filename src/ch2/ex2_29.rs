@@ -1,6 +1,7 @@
 use crate::{
-    list,
+    hlist, list,
     utils::{cons::Cons, list::List},
+    HList,
 };
 
 #[derive(PartialEq, Debug, Clone)]
@@ -268,3 +269,90 @@ fn test_cons_balance() {
     );
     assert_eq!(true, m.balanced());
 }
+
+// the same mobiles as HLists. the shape of a mobile is its type, so
+// the selectors can't fail and nothing is downcast
+type BranchH<S> = HList![i32, S];
+type MobileH<L, R> = HList![BranchH<L>, BranchH<R>];
+
+fn make_mobile<L, R>(left: BranchH<L>, right: BranchH<R>) -> MobileH<L, R> {
+    hlist![left, right]
+}
+
+fn make_branch<S>(length: i32, structure: S) -> BranchH<S> {
+    hlist![length, structure]
+}
+
+fn left_branch<L, R>(mobile: &MobileH<L, R>) -> &BranchH<L> {
+    mobile.car()
+}
+
+fn right_branch<L, R>(mobile: &MobileH<L, R>) -> &BranchH<R> {
+    mobile.cdr().car()
+}
+
+fn branch_length<S>(branch: &BranchH<S>) -> i32 {
+    *branch.car()
+}
+
+fn branch_structure<S>(branch: &BranchH<S>) -> &S {
+    branch.cdr().car()
+}
+
+// a structure is a weight or a mobile
+trait StructureH {
+    fn total_weight(&self) -> i32;
+    fn balanced(&self) -> bool;
+}
+
+impl StructureH for i32 {
+    fn total_weight(&self) -> i32 {
+        *self
+    }
+
+    fn balanced(&self) -> bool {
+        true
+    }
+}
+
+impl<L: StructureH, R: StructureH> StructureH for MobileH<L, R> {
+    fn total_weight(&self) -> i32 {
+        branch_structure(left_branch(self)).total_weight()
+            + branch_structure(right_branch(self)).total_weight()
+    }
+
+    fn balanced(&self) -> bool {
+        let (left, right) = (left_branch(self), right_branch(self));
+        let (l_st, r_st) = (branch_structure(left), branch_structure(right));
+        l_st.balanced()
+            && r_st.balanced()
+            && branch_length(left) * l_st.total_weight()
+                == branch_length(right) * r_st.total_weight()
+    }
+}
+
+#[test]
+fn test_hlist_total_weight() {
+    let m = make_mobile(
+        make_branch(10, 10),
+        make_branch(10, make_mobile(make_branch(3, 5), make_branch(9, 3))),
+    );
+    assert_eq!(18, m.total_weight());
+    let inner = branch_structure(right_branch(&m));
+    assert_eq!(List::from(*left_branch(inner)).to_string(), "(3 5)");
+}
+
+#[test]
+fn test_hlist_balance() {
+    let m = make_mobile(
+        make_branch(10, 10),
+        make_branch(10, make_mobile(make_branch(3, 5), make_branch(9, 3))),
+    );
+    assert!(!m.balanced());
+
+    let m = make_mobile(
+        make_branch(5, 20),
+        make_branch(10, make_mobile(make_branch(12, 4), make_branch(8, 6))),
+    );
+    assert!(m.balanced());
+}
@@ -1,25 +1,20 @@
-use crate::{list, utils::list::List};
+use std::collections::BTreeSet;
 
-fn square_tree(mut l: List) -> List {
-    fn inline_square_tree(list: &mut List) {
-        if let Some(mut car) = list.car_ref::<i32>() {
-            list.set_car((*car) * (*car));
-        }
-        if let Some(car) = list.car_mut::<List>() {
-            inline_square_tree(list.car_mut().unwrap());
-        }
-        if let Some(cdr) = list.cdr_mut() {
-            inline_square_tree(cdr);
-        }
-    }
-    inline_square_tree(&mut l);
-    l
+use test::Bencher;
+
+use crate::{
+    list,
+    utils::{list::List, rc_list::RcList, registry::Key, tree},
+};
+
+fn square_tree(l: List) -> List {
+    tree::tree_map(&l, |x| x.cast_ref::<i32>().unwrap().pow(2))
 }
 
 #[test]
 fn test_2_30_31() {
-    let tree = list!(1, list!(2, list!(3, 4), 5), list!(6, 7));
-    let expect = list!(1, list!(4, list!(9, 16), 25), list!(36, 49));
+    let tree = list!(1, [2, [3, 4], 5], [6, 7]);
+    let expect = list!(1, [4, [9, 16], 25], [36, 49]);
 
     assert!(test_tree_helper(&square_tree(tree), &expect));
 }
@@ -41,31 +36,76 @@ fn subsets(mut l: List) -> List {
     let mut rest = subsets(l.cdr().unwrap());
     let car = l.car_ref::<i32>().unwrap().clone();
 
-    let mut dummy = List::default();
+    let mut added = List::nil();
     for val in rest.iter() {
         let list = val.as_ref_any().downcast_ref::<List>().unwrap();
         let mut new = clone_i32_list(list);
         new.tail().set_cdr(Some(List::new(car)));
-        dummy.tail().set_cdr(Some(List::new(new)));
+        added.extend([new]);
     }
-    dummy.tail().set_cdr(Some(List::new(List::new(car))));
-    rest.tail().set_cdr(Some(dummy.cdr().unwrap()));
+    added.extend([List::new(car)]);
+    rest.tail().set_cdr(Some(added));
     rest
 }
 
 #[test]
 fn test_2_32() {
     let l = list!(1, 2, 3);
-    let expect = list!(
-        list!(3),
-        list!(3, 2),
-        list!(2),
-        list!(3, 1),
-        list!(3, 2, 1),
-        list!(2, 1),
-        list!(1)
+    let expect = list!([3], [3, 2], [2], [3, 1], [3, 2, 1], [2, 1], [1]);
+    assert!(test_tree_helper(&subsets(l.clone()), &expect));
+
+    // the same subsets in any order
+    let all: BTreeSet<_> = subsets(l).into_iter().map(Key).collect();
+    let shuffled = list!([1], [3, 2, 1], [2], [3, 1], [3], [2, 1], [3, 2]);
+    assert_eq!(all, shuffled.into_iter().map(Key).collect());
+    assert_eq!(all.first().unwrap().0.cast_ref::<List>(), Some(&list!(1)));
+}
+
+// (define (subsets s)
+//   (if (null? s)
+//       (list nil)
+//       (let ((rest (subsets (cdr s))))
+//         (append rest (map (lambda (x) (cons (car s) x)) rest)))))
+// every new subset is consed onto a subset of rest, nothing is copied
+fn subsets_rc(s: &RcList) -> RcList {
+    let Some(cdr) = s.cdr() else {
+        return RcList::new(RcList::nil());
+    };
+    let rest = subsets_rc(&cdr);
+    let car = *s.car_ref::<i32>().unwrap();
+    let added = rest.map(|x| RcList::cons(car, x.cast_ref::<RcList>().unwrap().clone()));
+    rest.append(&added)
+}
+
+#[test]
+fn test_2_32_rc() {
+    let s: RcList = [1, 2, 3].into_iter().collect();
+    let all = subsets_rc(&s);
+    assert_eq!(
+        all.to_string(),
+        "(() (3) (2) (2 3) (1) (1 3) (1 2) (1 2 3))"
     );
-    assert!(test_tree_helper(&subsets(l), &expect))
+
+    // the second half shares its tails with the first half
+    let subsets: Vec<_> = all
+        .iter()
+        .map(|x| x.cast_ref::<RcList>().unwrap())
+        .collect();
+    for (with, without) in subsets[4..].iter().zip(&subsets[..4]) {
+        assert!(with.cdr().unwrap().ptr_eq(without));
+    }
+    assert_eq!(List::from(&all).len(), 8);
+}
+
+#[bench]
+fn bench_subsets_list(b: &mut Bencher) {
+    b.iter(|| subsets(test::black_box((1..=12).collect())));
+}
+
+#[bench]
+fn bench_subsets_rc_list(b: &mut Bencher) {
+    let s: RcList = (1..=12).collect();
+    b.iter(|| subsets_rc(test::black_box(&s)));
 }
 
 fn test_tree_helper(l1: &List, l2: &List) -> bool {
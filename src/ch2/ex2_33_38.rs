@@ -7,9 +7,11 @@ use std::{
     vec,
 };
 
+use test::Bencher;
+
 use crate::{
-    list,
-    utils::{cons::ConsAny, list::List},
+    list, match_list,
+    utils::{cons::ConsAny, list::List, tree, value::Value},
 };
 
 fn square<T>(x: Box<dyn ConsAny>) -> T
@@ -67,15 +69,9 @@ fn mul(acc: i32, car: Box<dyn ConsAny>) -> i32 {
     acc
 }
 
-fn cons(acc: Option<List>, car: Box<dyn ConsAny>) -> Option<List> {
+fn cons(mut acc: List, car: Box<dyn ConsAny>) -> List {
     if let Ok(cur) = (car as Box<dyn Any>).downcast::<i32>() {
-        match acc {
-            None => return Some(List::new(*cur)),
-            Some(mut acc) => {
-                acc.tail().set_cdr(Some(List::new(*cur)));
-                return Some(acc);
-            }
-        };
+        acc.extend([*cur]);
     }
     acc
 }
@@ -94,55 +90,40 @@ fn test_accumulate() {
     let expect = vec![1, 2, 3, 4, 5];
     assert!(l
         .into_iter()
-        .fold(None, cons)
-        .unwrap()
+        .fold(List::nil(), cons)
         .iter()
         .eq(expect.iter()));
 }
 
-fn enumerate_interval(low: i32, high: i32) -> Option<List> {
-    if low > high {
-        return None;
-    }
-    let mut now = List::new(low);
-    now.set_cdr(enumerate_interval(low + 1, high));
-    Some(now)
+fn enumerate_interval(low: i32, high: i32) -> List {
+    // (cons low (enumerate-interval (+ low 1) high)) recurses per
+    // element, build it with a loop instead
+    (low..=high).collect()
 }
 
 #[test]
 fn test_enumerate_interval() {
     let expect = vec![2, 3, 4, 5, 6, 7];
-    assert!(enumerate_interval(2, 7).unwrap().iter().eq(expect.iter()))
-}
-
-fn enumerate_tree(mut l: Option<List>) -> Option<List> {
-    l.map(|mut list| {
-        let mut dummy = List::default();
-        if list.car_ref::<i32>().is_some() {
-            let cdr = enumerate_tree(list.cdr());
-            dummy.tail().set_cdr(Some(list));
-            dummy.tail().set_cdr(cdr);
-        } else if list.car_ref::<List>().is_some() {
-            dummy.tail().set_cdr(enumerate_tree(list.car()));
-            dummy.tail().set_cdr(enumerate_tree(list.cdr()));
-        }
-        dummy.cdr().unwrap()
-    })
+    assert!(enumerate_interval(2, 7).iter().eq(expect.iter()));
+    assert!(enumerate_interval(7, 2).is_null());
+}
+
+fn enumerate_tree(l: List) -> List {
+    tree::fringe(&l)
 }
 
 #[test]
 fn test_enumerate_tree() {
-    let tree = list!(1, list!(2, list!(3, 4)), 5);
+    let tree = list!(1, [2, [3, 4]], 5);
     let expect = vec![1, 2, 3, 4, 5];
-    assert!(enumerate_tree(Some(tree)).unwrap().iter().eq(expect.iter()));
+    assert!(enumerate_tree(tree).iter().eq(expect.iter()));
 }
 
 #[test]
 fn test_sum_odd_squares() {
-    let tree = list!(1, list!(2, list!(3, 4)), 5);
+    let tree = list!(1, [2, [3, 4]], 5);
     assert_eq!(
-        enumerate_tree(Some(tree))
-            .unwrap()
+        enumerate_tree(tree)
             .into_iter()
             .filter(is_odd::<i32>)
             .collect::<List>() // filter
@@ -188,7 +169,6 @@ fn map_fib() -> impl FnMut(Box<dyn ConsAny>) -> i32 {
 
 fn even_fibs(n: i32) -> List {
     enumerate_interval(0, n)
-        .unwrap()
         .into_iter()
         .map(map_fib())
         .collect::<List>()
@@ -196,8 +176,7 @@ fn even_fibs(n: i32) -> List {
         .filter(is_even::<i32>)
         .collect::<List>()
         .into_iter()
-        .fold(None, cons)
-        .unwrap()
+        .fold(List::nil(), cons)
 }
 
 #[test]
@@ -209,7 +188,6 @@ fn test_even_fibs() {
 
 fn lib_fib_squares(n: i32) -> List {
     enumerate_interval(0, n)
-        .unwrap()
         .into_iter()
         .map(map_fib())
         .collect::<List>()
@@ -217,8 +195,7 @@ fn lib_fib_squares(n: i32) -> List {
         .map(square::<i32>)
         .collect::<List>()
         .into_iter()
-        .fold(None, cons)
-        .unwrap()
+        .fold(List::nil(), cons)
 }
 
 #[test]
@@ -229,19 +206,14 @@ fn test_lib_fib_squares() {
 }
 
 fn acc_map<T: ConsAny>(mut p: impl FnMut(Box<dyn ConsAny>) -> T, l: List) -> List {
-    l.into_iter()
-        .fold(None, |acc, cur| match acc {
-            None => Some(List::new(p(cur))),
-            Some(mut acc) => {
-                acc.tail().set_cdr(Some(List::new(p(cur))));
-                Some(acc)
-            }
-        })
-        .unwrap()
+    l.into_iter().fold(List::nil(), |mut acc, cur| {
+        acc.extend([p(cur)]);
+        acc
+    })
 }
 
 fn append(l1: List, l2: List) -> List {
-    l2.into_iter().fold(Some(l1), cons).unwrap()
+    l2.into_iter().fold(l1, cons)
 }
 
 fn length(l: List) -> i32 {
@@ -283,42 +255,30 @@ fn test_2_34() {
 }
 
 fn count_leaves(l: List) -> i32 {
-    l.into_iter()
-        .map(|cur| {
-            if let Ok(ll) = (cur as Box<dyn Any>).downcast::<List>() {
-                return count_leaves(*ll);
-            }
-            1
-        })
-        .collect::<List>()
-        .into_iter()
-        .fold(0, |mut acc, cur| {
-            acc += *(cur as Box<dyn Any>).downcast::<i32>().unwrap();
-            acc
-        })
+    tree::count_leaves(&l) as i32
 }
 
 #[test]
 fn test_2_35() {
-    let a = list!(list!(1, 2), 3, 4);
+    let a = list!([1, 2], 3, 4);
     assert_eq!(a.len(), 3);
     assert_eq!(count_leaves(a), 4);
 
-    let aa = list!(list!(1, 2), 3, 4);
-    let ab = list!(list!(1, 2), 3, 4);
+    let aa = list!([1, 2], 3, 4);
+    let ab = list!([1, 2], 3, 4);
     let b = list!(aa, ab);
     assert_eq!(count_leaves(b), 8);
 }
 
 // (cons (accumulate op init (map car seqs))
 //       (accumulate-n op init (map cdr seqs)))))
-fn accumulate_n<T, F>(mut op: F, init: T, mut l: List) -> Option<List>
+fn accumulate_n<T, F>(mut op: F, init: T, mut l: List) -> List
 where
     T: ConsAny + Clone,
     F: FnMut(T, Box<dyn ConsAny>) -> T,
 {
-    if l.car_ref::<List>().is_none() {
-        return None;
+    if l.car_ref::<List>().is_none_or(List::is_null) {
+        return List::nil();
     }
     let mut new = List::new(
         l.iter_mut()
@@ -328,49 +288,44 @@ where
             .fold(init.clone(), &mut op),
     );
 
-    if l.car_ref::<List>().unwrap().cdr_ref().is_some() {
-        let cdr = l
-            .into_iter()
-            .map(|car| {
-                (car as Box<dyn Any>)
-                    .downcast::<List>()
-                    .unwrap()
-                    .cdr()
-                    .unwrap()
-            })
-            .collect();
-        new.set_cdr(accumulate_n(op, init.clone(), cdr));
-    }
-    Some(new)
+    let cdr = l
+        .into_iter()
+        .map(|car| {
+            (car as Box<dyn Any>)
+                .downcast::<List>()
+                .unwrap()
+                .cdr()
+                .unwrap_or_default()
+        })
+        .collect();
+    new.set_cdr(Some(accumulate_n(op, init.clone(), cdr)));
+    new
 }
 
 #[test]
 fn test_accumulate_n() {
-    let l = list!(
-        list!(1, 2, 3),
-        list!(4, 5, 6),
-        list!(7, 8, 9),
-        list!(10, 11, 12)
-    );
+    let l = list!([1, 2, 3], [4, 5, 6], [7, 8, 9], [10, 11, 12]);
     let expect = vec![22, 26, 30];
-    assert!(accumulate_n(plus, 0, l).unwrap().iter().eq(expect.iter()))
-}
-
-// v, w: list!(i32, i32, ...)
-fn dot_product(v: &List, mut w: &List) -> i32 {
-    v.into_iter()
-        .map(|a| {
-            let w_car = w.car_ref::<i32>().unwrap();
-            let m: i32 = a.cast_ref::<i32>().unwrap() * w_car;
-            if w.cdr_ref().is_some() {
-                w = w.cdr_ref().unwrap();
-            }
-            m
-        })
-        .fold(0, |mut acc, item| {
-            acc += item;
-            acc
-        })
+    assert!(accumulate_n(plus, 0, l).iter().eq(expect.iter()))
+}
+
+// v, w: list!(i32, i32, ...), as long as the shorter one
+fn dot_product(mut v: &List, mut w: &List) -> i32 {
+    let mut acc = 0;
+    loop {
+        match_list!(v,
+            (a: i32, ..) => match_list!(w,
+                (b: i32, ..) => acc += a * b,
+                _ => return acc,
+            ),
+            _ => return acc,
+        );
+        // cdr_ref is None past the last pair, that ends the shorter vector
+        match (v.cdr_ref(), w.cdr_ref()) {
+            (Some(rest_v), Some(rest_w)) => (v, w) = (rest_v, rest_w),
+            _ => return acc,
+        }
+    }
 }
 
 fn matrix_times_vector(m: List, v: List) -> List {
@@ -384,16 +339,12 @@ fn matrix_times_vector(m: List, v: List) -> List {
 
 fn list_cons(mut acc: List, l: Box<dyn ConsAny>) -> List {
     let l = *(l as Box<dyn Any>).downcast::<i32>().unwrap();
-    if acc.car_ref::<i32>().is_none() {
-        List::new(l)
-    } else {
-        acc.tail().set_cdr(Some(List::new(l)));
-        acc
-    }
+    acc.extend([l]);
+    acc
 }
 
 fn transpose(m: List) -> List {
-    accumulate_n(list_cons, List::new(0.1), m).unwrap()
+    accumulate_n(list_cons, List::nil(), m)
 }
 
 /*
@@ -427,11 +378,11 @@ fn test_2_37() {
     assert_eq!(dot_product(&v, &w), 20);
 
     let v = list!(3, 2, 1);
-    let m = list!(list!(1, 2, 3), list!(3, 5, 6), list!(7, 8, 9));
+    let m = list!([1, 2, 3], [3, 5, 6], [7, 8, 9]);
     let expect = vec![10, 25, 46];
     assert!(matrix_times_vector(m, v).iter().eq(expect.iter()));
 
-    let m = list!(list!(1, 2, 3, 4), list!(4, 5, 6, 7), list!(8, 9, 10, 11));
+    let m = list!([1, 2, 3, 4], [4, 5, 6, 7], [8, 9, 10, 11]);
     let expect = vec![vec![1, 4, 8], vec![2, 5, 9], vec![3, 6, 10], vec![4, 7, 11]];
     let tran = transpose(m);
     let mut idx = 0;
@@ -441,8 +392,8 @@ fn test_2_37() {
         idx += 1;
     }
 
-    let m = list!(list!(1, 2), list!(3, 4), list!(5, 6));
-    let n = list!(list!(1, 2, 3), list!(4, 5, 6));
+    let m = list!([1, 2], [3, 4], [5, 6]);
+    let n = list!([1, 2, 3], [4, 5, 6]);
     let expect = vec![vec![9, 12, 15], vec![19, 26, 33], vec![29, 40, 51]];
     let result = matrix_times_matrix(m, n);
     let mut idx = 0;
@@ -454,6 +405,89 @@ fn test_2_37() {
     }
 }
 
+// the same matrix code over Value, elements are matched instead of
+// downcast
+fn dot_product_value(v: &Value, w: &Value) -> i64 {
+    v.iter()
+        .zip(w)
+        .map(|pair| match pair {
+            (Value::Int(a), Value::Int(b)) => a * b,
+            _ => panic!("dot-product: not an integer"),
+        })
+        .sum()
+}
+
+// walks every seq at once, seqs[i] is the rest of the i-th sequence
+fn accumulate_n_value<F>(mut op: F, init: Value, seqs: &Value) -> Value
+where
+    F: FnMut(&Value, Value) -> Value,
+{
+    let mut seqs: Vec<&Value> = seqs.iter().collect();
+    let mut result = vec![];
+    while seqs.first().is_some_and(|seq| seq.is_pair()) {
+        let acc = seqs
+            .iter()
+            .rev()
+            .fold(init.clone(), |acc, seq| op(seq.car().unwrap(), acc));
+        result.push(acc);
+        seqs.iter_mut().for_each(|seq| *seq = seq.cdr().unwrap());
+    }
+    result.into_iter().collect()
+}
+
+fn transpose_value(m: &Value) -> Value {
+    accumulate_n_value(|x, acc| Value::cons(x.clone(), acc), Value::Nil, m)
+}
+
+fn matrix_times_matrix_value(m: &Value, n: &Value) -> Value {
+    let cols = transpose_value(n);
+    m.iter()
+        .map(|row| {
+            cols.iter()
+                .map(|col| Value::Int(dot_product_value(row, col)))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_2_37_value() {
+    let sums = accumulate_n_value(
+        |x, acc| match (x, acc) {
+            (Value::Int(x), Value::Int(acc)) => Value::Int(x + acc),
+            _ => Value::Nil,
+        },
+        Value::Int(0),
+        &Value::from(&list!([1, 2, 3], [4, 5, 6], [7, 8, 9])),
+    );
+    assert_eq!(sums, Value::list([12, 15, 18]));
+
+    let m = list!([1, 2], [3, 4], [5, 6]);
+    let n = list!([1, 2, 3], [4, 5, 6]);
+    let expect = matrix_times_matrix(m.clone(), n.clone());
+    let result = matrix_times_matrix_value(&Value::from(&m), &Value::from(&n));
+    assert_eq!(result, Value::from(&expect));
+    assert_eq!(result.to_string(), "((9 12 15) (19 26 33) (29 40 51))");
+}
+
+fn bench_matrix() -> List {
+    (0..30)
+        .map(|i| (0..30).map(|j| i * 30 + j).collect::<List>())
+        .collect()
+}
+
+#[bench]
+fn bench_matrix_times_matrix_list(b: &mut Bencher) {
+    let m = bench_matrix();
+    b.iter(|| matrix_times_matrix(test::black_box(m.clone()), m.clone()));
+}
+
+#[bench]
+fn bench_matrix_times_matrix_value(b: &mut Bencher) {
+    let m = Value::from(&bench_matrix());
+    b.iter(|| matrix_times_matrix_value(test::black_box(&m), &m));
+}
+
 fn div(acc: f64, car: Box<dyn ConsAny>) -> f64 {
     acc / car.cast_ref::<f64>().unwrap()
 }
@@ -463,11 +497,20 @@ where
     T: ConsAny + Clone,
     F: FnMut(T, Box<dyn ConsAny>) -> T,
 {
-    if l.cdr_ref().is_none() {
-        return op(l.car::<T>().unwrap(), Box::new(result));
+    // recurse through a &mut F helper, passing `&mut op` to fold_right
+    // itself would instantiate fold_right::<T, &mut &mut ... F> forever
+    fn fold<T, F>(op: &mut F, result: T, mut l: List) -> T
+    where
+        T: ConsAny + Clone,
+        F: FnMut(T, Box<dyn ConsAny>) -> T,
+    {
+        if l.cdr_ref().is_none() {
+            return op(l.car::<T>().unwrap(), Box::new(result));
+        }
+        let next = fold(op, result, l.cdr().unwrap());
+        op(l.car::<T>().unwrap(), Box::new(next))
     }
-    let next = fold_right(&mut op, result, l.cdr().unwrap());
-    op(l.car::<T>().unwrap(), Box::new(next))
+    fold(&mut op, result, l)
 }
 
 fn fold_left<T, F>(mut op: F, result: T, mut l: List) -> T
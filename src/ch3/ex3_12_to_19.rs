@@ -0,0 +1,93 @@
+use crate::{
+    quote,
+    utils::pair_graph::{Datum, PairGraph, PairId},
+};
+
+fn pair(datum: &Datum) -> PairId {
+    datum.as_pair().unwrap()
+}
+
+#[test]
+fn test_3_12() {
+    let mut g = PairGraph::new();
+    let x = g.add_list(&quote!((a b)));
+    let y = g.add_list(&quote!((c d)));
+    // (append x y) copies x
+    let z = g.add_list(&g.to_list(&x));
+    g.append_mut(pair(&z), y.clone());
+    assert_eq!(g.display(&z).to_string(), "(a b c d)");
+    assert_eq!(g.display(g.cdr(pair(&x))).to_string(), "(b)");
+    let w = g.append_mut(pair(&x), y).into();
+    assert_eq!(g.display(&w).to_string(), "(a b c d)");
+    assert_eq!(g.display(g.cdr(pair(&x))).to_string(), "(b c d)");
+}
+
+#[test]
+fn test_3_13() {
+    let mut g = PairGraph::new();
+    let z = g.add_list(&quote!((a b c)));
+    g.make_cycle(pair(&z));
+    assert_eq!(g.display(&z).to_string(), "#0=(a b c . #0#)");
+}
+
+// reverses x in place
+fn mystery(g: &mut PairGraph, mut x: Datum) -> Datum {
+    let mut y = Datum::Nil;
+    while let Datum::Pair(p) = x {
+        let temp = g.set_cdr(p, y);
+        y = x;
+        x = temp;
+    }
+    y
+}
+
+#[test]
+fn test_3_14() {
+    let mut g = PairGraph::new();
+    let v = g.add_list(&quote!((a b c d)));
+    let w = mystery(&mut g, v.clone());
+    assert_eq!(g.display(&v).to_string(), "(a)");
+    assert_eq!(g.display(&w).to_string(), "(d c b a)");
+}
+
+// ex 3.16, counts a pair once per path to it and never ends on a cycle
+fn count_pairs(g: &PairGraph, x: &Datum) -> usize {
+    match x {
+        Datum::Pair(p) => count_pairs(g, g.car(*p)) + count_pairs(g, g.cdr(*p)) + 1,
+        _ => 0,
+    }
+}
+
+#[test]
+fn test_3_16_17() {
+    let mut g = PairGraph::new();
+    let three = g.add_list(&quote!((a b c)));
+
+    // (define x (list 'a)) (list x x)
+    let x = g.add_list(&quote!((a)));
+    let xs = g.cons(x.clone(), Datum::Nil);
+    let four = Datum::from(g.cons(x, xs.into()));
+
+    // (define x (list 'a)) (define y (cons x x)) (cons y y)
+    let x = g.add_list(&quote!((a)));
+    let y = Datum::from(g.cons(x.clone(), x));
+    let seven = Datum::from(g.cons(y.clone(), y));
+
+    assert_eq!(count_pairs(&g, &three), 3);
+    assert_eq!(count_pairs(&g, &four), 4);
+    assert_eq!(count_pairs(&g, &seven), 7);
+    assert_eq!(g.display(&seven).to_string(), "(#0=(#1=(a) . #1#) . #0#)");
+    for structure in [&three, &four, &seven] {
+        assert_eq!(g.count_pairs(structure), 3);
+    }
+}
+
+#[test]
+fn test_3_18_19() {
+    let mut g = PairGraph::new();
+    let z = g.add_list(&quote!((a b c)));
+    assert!(!g.is_circular(&z));
+    g.make_cycle(pair(&z));
+    assert!(g.is_circular(&z));
+    assert_eq!(g.find_cycle(&z).map(|cycle| cycle.len), Some(3));
+}
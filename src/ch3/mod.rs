@@ -0,0 +1 @@
+mod ex3_12_to_19;
@@ -12,4 +12,5 @@ extern crate test;
 
 mod ch1;
 mod ch2;
+mod ch3;
 pub mod utils;
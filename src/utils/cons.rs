@@ -1,10 +1,14 @@
-use std::{any::Any, fmt::Debug, marker::PhantomData, ptr::NonNull};
+use std::{any::Any, fmt::Debug, mem};
+
+use super::list::List;
 
 pub trait ConsAny: Any + Debug {
     fn dyn_clone(&self) -> Box<dyn ConsAny>;
     fn as_ref_any(&self) -> &dyn Any;
     fn as_mut_any(&mut self) -> &mut dyn Any;
     fn dyn_eq(&self, other: &dyn ConsAny) -> bool;
+    // name of the concrete type, for error messages
+    fn type_name(&self) -> &'static str;
 }
 
 impl PartialEq for dyn ConsAny {
@@ -52,6 +56,21 @@ where
         }
         false
     }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name_of_val(self)
+    }
+}
+
+// box a value as dyn ConsAny, a value that already is a Box<dyn ConsAny>
+// is moved as is instead of being boxed twice, otherwise the result will be
+// Box<Box<dyn ConsAny>> and dyn_eq fails for T & Box<T>
+pub fn into_box<T: ConsAny>(value: T) -> Box<dyn ConsAny> {
+    let value: Box<dyn Any> = Box::new(value);
+    match value.downcast::<Box<dyn ConsAny>>() {
+        Ok(boxed) => *boxed,
+        Err(value) => value.downcast::<T>().unwrap(),
+    }
 }
 
 impl dyn ConsAny {
@@ -64,115 +83,87 @@ impl dyn ConsAny {
     }
 }
 
-pub type Link = Option<NonNull<dyn ConsAny>>;
+pub type Link = Option<Box<dyn ConsAny>>;
 
-#[derive(Debug, Clone)]
+// a pair owns its car and cdr, cloning one deep clones both
+#[derive(Clone)]
 pub struct Cons {
-    pub car: Link,
-    pub cdr: Link,
-    marker: PhantomData<Box<dyn ConsAny>>,
+    car: Link,
+    cdr: Link,
 }
 
 impl Cons {
     pub fn new(car: Option<Box<dyn ConsAny>>, cdr: Option<Box<dyn ConsAny>>) -> Cons {
-        Cons {
-            car: car.map(|car| Box::leak(car).into()),
-            cdr: cdr.map(|cdr| Box::leak(cdr).into()),
-            marker: PhantomData,
-        }
+        Cons { car, cdr }
     }
 
-    // since all cons holds Box<dyn ConsAny>, should cast as box first
     pub fn car_ref(&self) -> Option<&dyn ConsAny> {
-        self.car.map(|car| unsafe { &*car.as_ptr() })
+        self.car.as_deref()
     }
 
     pub fn car_mut(&mut self) -> Option<&mut dyn ConsAny> {
-        self.car.map(|car| unsafe { &mut *car.as_ptr() })
+        self.car.as_deref_mut()
     }
 
+    // returns the replaced car
     pub fn set_car<T: ConsAny>(&mut self, car: Option<T>) -> Option<Box<dyn ConsAny>> {
-        // clean self.car
-        let re = self.car();
-        self.car = car.map(|car| Box::leak(Box::new(car) as Box<dyn ConsAny>).into());
-        re
+        mem::replace(&mut self.car, car.map(into_box))
     }
 
     pub fn car(&mut self) -> Option<Box<dyn ConsAny>> {
-        self.car.map(|car| unsafe {
-            self.car = None;
-            Box::from_raw(car.as_ptr())
-        })
+        self.car.take()
     }
 
+    // leaves the car in place when it is not a T
     pub fn car_downcast<T: Any>(&mut self) -> Option<Box<T>> {
-        match self.car() {
-            Some(car) => match (car as Box<dyn Any>).downcast() {
-                Ok(car) => Some(car),
-                Err(_) => None,
-            },
-            None => None,
-        }
+        self.car_downcast_ref::<T>()?;
+        let car: Box<dyn Any> = self.car()?;
+        car.downcast().ok()
     }
 
     pub fn car_downcast_ref<T: Any>(&self) -> Option<&T> {
-        match self.car {
-            Some(car) => unsafe { (*car.as_ptr()).cast_ref() },
-            None => None,
-        }
+        self.car_ref()?.cast_ref()
     }
 
     pub fn car_downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
-        match self.car {
-            Some(car) => unsafe { (*car.as_ptr()).cast_mut() },
-            None => None,
-        }
+        self.car_mut()?.cast_mut()
     }
 
     pub fn cdr_ref(&self) -> Option<&dyn ConsAny> {
-        self.cdr.map(|cdr| unsafe { &*cdr.as_ptr() })
+        self.cdr.as_deref()
     }
 
     pub fn cdr_mut(&mut self) -> Option<&mut dyn ConsAny> {
-        self.cdr.map(|cdr| unsafe { &mut *cdr.as_ptr() })
+        self.cdr.as_deref_mut()
     }
 
+    // returns the replaced cdr
     pub fn set_cdr<T: ConsAny>(&mut self, cdr: Option<T>) -> Option<Box<dyn ConsAny>> {
-        // clean self cdr
-        let re = self.cdr();
-        self.cdr = cdr.map(|cdr| Box::leak(Box::new(cdr) as Box<dyn ConsAny>).into());
-        re
+        mem::replace(&mut self.cdr, cdr.map(into_box))
     }
 
     pub fn cdr(&mut self) -> Option<Box<dyn ConsAny>> {
-        self.cdr.map(|cdr| unsafe {
-            self.cdr = None;
-            Box::from_raw(cdr.as_ptr())
-        })
+        self.cdr.take()
     }
 
+    // leaves the cdr in place when it is not a T
     pub fn cdr_downcast<T: Any>(&mut self) -> Option<Box<T>> {
-        match self.cdr() {
-            Some(cdr) => match (cdr as Box<dyn Any>).downcast() {
-                Ok(cdr) => Some(cdr),
-                Err(_) => None,
-            },
-            None => None,
-        }
+        self.cdr_downcast_ref::<T>()?;
+        let cdr: Box<dyn Any> = self.cdr()?;
+        cdr.downcast().ok()
     }
 
     pub fn cdr_downcast_ref<T: Any>(&self) -> Option<&T> {
-        match self.cdr {
-            Some(cdr) => unsafe { (*cdr.as_ptr()).cast_ref() },
-            None => None,
-        }
+        self.cdr_ref()?.cast_ref()
     }
 
     pub fn cdr_downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
-        match self.cdr {
-            Some(cdr) => unsafe { (*cdr.as_ptr()).cast_mut() },
-            None => None,
-        }
+        self.cdr_mut()?.cast_mut()
+    }
+
+    // car and cdr borrowed mutably at the same time
+    pub fn split_mut(&mut self) -> (Option<&mut dyn ConsAny>, Option<&mut dyn ConsAny>) {
+        (self.car.as_deref_mut(), self.cdr.as_deref_mut())
     }
 }
 
@@ -182,13 +173,46 @@ impl Default for Cons {
     }
 }
 
+// a cdr seen as the rest of a list
+enum Rest<'a> {
+    End,
+    // the next pair, and whether it's held by a List or a bare Cons
+    Pair(&'a Cons, bool),
+    Tail(&'a dyn ConsAny),
+}
+
+fn rest(cdr: Option<&dyn ConsAny>) -> Rest<'_> {
+    let Some(cdr) = cdr else {
+        return Rest::End;
+    };
+    if let Some(list) = cdr.cast_ref::<List>() {
+        return match list.as_cons() {
+            Some(cons) => Rest::Pair(cons, true),
+            None => Rest::End,
+        };
+    }
+    match cdr.cast_ref::<Cons>() {
+        Some(cons) => Rest::Pair(cons, false),
+        None => Rest::Tail(cdr),
+    }
+}
+
+// the pair held by a cdr, when the cdr is a List or a Cons
+fn rest_mut(cdr: &mut dyn ConsAny) -> Option<&mut Cons> {
+    if cdr.cast_ref::<List>().is_some() {
+        return cdr.cast_mut::<List>().unwrap().as_cons_mut();
+    }
+    cdr.cast_mut::<Cons>()
+}
+
+// drop and eq walk the cdr chain in a loop, recursing per element
+// would overflow the stack for long lists
 impl Drop for Cons {
     fn drop(&mut self) {
-        if let Some(car) = self.car() {
-            drop(car)
-        }
-
-        if let Some(cdr) = self.cdr() {
+        let mut next = self.cdr();
+        while let Some(mut cdr) = next {
+            // detach the rest before cdr is dropped
+            next = rest_mut(&mut *cdr).and_then(Cons::cdr);
             drop(cdr)
         }
     }
@@ -205,12 +229,30 @@ impl PartialEq<Cons> for Cons {
             }
             a.unwrap().dyn_eq(b.unwrap())
         }
-        _eq(self.car_ref(), other.car_ref()) && _eq(self.cdr_ref(), other.cdr_ref())
+
+        let (mut a, mut b) = (self, other);
+        loop {
+            if !_eq(a.car_ref(), b.car_ref()) {
+                return false;
+            }
+            match (rest(a.cdr_ref()), rest(b.cdr_ref())) {
+                (Rest::End, Rest::End) => return true,
+                (Rest::Pair(x, x_list), Rest::Pair(y, y_list)) if x_list == y_list => {
+                    (a, b) = (x, y);
+                }
+                (Rest::Tail(x), Rest::Tail(y)) => return x.dyn_eq(y),
+                _ => return false,
+            }
+        }
     }
 }
 
+// these cover the ownership of cars and cdrs, run them under miri:
+// cargo miri test utils::cons
 mod test {
     use super::*;
+    use crate::list;
+    use crate::utils::list::{DottedIterator, Element};
 
     #[test]
     fn basic_cons() {
@@ -223,4 +265,75 @@ mod test {
         assert_eq!(l1.car_ref().unwrap(), &1);
         assert_eq!(list2.cdr_ref().unwrap(), &3);
     }
+
+    #[test]
+    fn clone_cons() {
+        let pair = Cons::new(Some(Box::new(list!(1, 2))), Some(Box::new("s".to_string())));
+        let mut copy = pair.clone();
+        assert_eq!(copy, pair);
+
+        // the clone owns its own car and cdr
+        copy.car_downcast_mut::<List>().unwrap().set_car(3);
+        copy.set_cdr(Some(4));
+        assert_eq!(pair.car_ref().unwrap(), &list!(1, 2));
+        assert_eq!(pair.cdr_ref().unwrap(), &"s".to_string());
+        drop(pair);
+        assert_eq!(copy.car_ref().unwrap(), &list!(3, 2));
+        assert_eq!(copy.cdr_ref().unwrap(), &4);
+    }
+
+    #[test]
+    fn replace_car_cdr() {
+        let mut pair = Cons::new(Some(Box::new(1)), Some(Box::new(list!(2))));
+        let old = pair.set_car(Some("a".to_string())).unwrap();
+        assert_eq!(*old, 1);
+        let old = pair.set_cdr(Some(list!(3, 4))).unwrap();
+        assert_eq!(*old, list!(2));
+        assert_eq!(pair.to_string(), "(\"a\" 3 4)");
+
+        // a boxed value is stored as is, not boxed twice
+        pair.set_car(Some(Box::new(5) as Box<dyn ConsAny>));
+        assert_eq!(pair.car_ref().unwrap(), &5);
+
+        // a mismatched downcast leaves the value in place
+        assert!(pair.car_downcast::<String>().is_none());
+        assert_eq!(*pair.car_downcast::<i32>().unwrap(), 5);
+        assert!(pair.car_ref().is_none());
+        assert!(pair.cdr_downcast::<i32>().is_none());
+        assert_eq!(*pair.cdr_downcast::<List>().unwrap(), list!(3, 4));
+        assert!(pair.set_cdr(None::<i32>).is_none());
+    }
+
+    #[test]
+    fn iterate_nested() {
+        let mut l = list!(1, list!(2, list!(3)), 4; 5);
+        for item in l.iter_mut() {
+            if let Some(n) = item.cast_mut::<i32>() {
+                *n *= 10;
+            }
+            if let Some(inner) = item.cast_mut::<List>() {
+                inner.iter_mut().for_each(|x| {
+                    if let Some(n) = x.cast_mut::<i32>() {
+                        *n += 1
+                    }
+                });
+            }
+        }
+        assert_eq!(l, list!(10, list!(3, list!(3)), 40; 5));
+
+        let mut iter = l.iter_mut().dotted();
+        let first = iter.next().unwrap();
+        let second = iter.next().unwrap();
+        // items handed out by iter_mut don't alias, both are usable
+        if let (Element::Car(a), Element::Car(b)) = (first, second) {
+            *a.cast_mut::<i32>().unwrap() += 1;
+            b.cast_mut::<List>().unwrap().set_car(0);
+        }
+
+        let copy = l.clone();
+        let items: Vec<_> = l.into_iter().dotted().collect();
+        assert_eq!(items.len(), 4);
+        assert_eq!(copy.to_string(), "(11 (0 (3)) 40 . 5)");
+        assert!(copy.iter().eq(copy.clone().iter()));
+    }
 }
@@ -0,0 +1,365 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display},
+};
+
+use super::{
+    cons::ConsAny,
+    list::{wrong_type, List, ListError},
+};
+
+// c[ad]+r accessors: (caddr x) is (car (cdr (cdr x))), the letters
+// between c and r are applied from right to left. the car or cdr of
+// anything but a pair is an error, the cdr at the end of a list is '()
+//
+// cxr!(l, "caddr")            Result<Cow<dyn ConsAny>, PathError>
+// cxr!(l, "caddr" as i32)     Result<Cow<i32>, PathError>
+// cxr!(mut l, "caddr")        &mut, also with `as T`
+// cxr!(move l, "caddr")       Box<dyn ConsAny> or T, the rest of l is dropped
+//
+// the '() after the last pair isn't stored, so the borrowing accessors
+// hand out a new one for it, like List::list_tail, and the mutable ones
+// fail with ListError::NilCdr
+//
+// a literal path that is not c[ad]+r doesn't compile. paths known only
+// at runtime go through List::path and friends, which return
+// PathError::Malformed for them
+#[macro_export]
+macro_rules! cxr {
+    (@check $path:literal) => {
+        const _: () = assert!(
+            $crate::utils::cxr::is_cxr($path),
+            concat!("cxr: ", stringify!($path), " is not c[ad]+r")
+        );
+    };
+    (mut $list:expr, $path:literal as $t:ty) => {{
+        $crate::cxr!(@check $path);
+        $list.path_as_mut::<$t>($path)
+    }};
+    (mut $list:expr, $path:literal) => {{
+        $crate::cxr!(@check $path);
+        $list.path_mut($path)
+    }};
+    (move $list:expr, $path:literal as $t:ty) => {{
+        $crate::cxr!(@check $path);
+        $list.into_path_as::<$t>($path)
+    }};
+    (move $list:expr, $path:literal) => {{
+        $crate::cxr!(@check $path);
+        $list.into_path($path)
+    }};
+    ($list:expr, $path:literal as $t:ty) => {{
+        $crate::cxr!(@check $path);
+        $list.path_as::<$t>($path)
+    }};
+    ($list:expr, $path:literal) => {{
+        $crate::cxr!(@check $path);
+        $list.path($path)
+    }};
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+    // the path is not c[ad]+r
+    Malformed(String),
+    // step is the index of the car or cdr that failed, counting from
+    // the first one applied, or the number of steps when the final
+    // downcast failed
+    Step {
+        path: String,
+        step: usize,
+        error: ListError,
+    },
+}
+
+impl PathError {
+    pub fn path(&self) -> &str {
+        match self {
+            PathError::Malformed(path) | PathError::Step { path, .. } => path,
+        }
+    }
+
+    pub fn step(&self) -> Option<usize> {
+        match self {
+            PathError::Malformed(_) => None,
+            PathError::Step { step, .. } => Some(*step),
+        }
+    }
+
+    // the expression that failed, e.g. (car (cdr list)) for step 1 of cadr
+    pub fn expression(&self) -> Option<String> {
+        let PathError::Step { path, step, .. } = self else {
+            return None;
+        };
+        let ops = ops(path).ok()?;
+        let applied = &ops[..ops.len().min(step + 1)];
+        let mut expr = "list".to_string();
+        for op in applied {
+            expr = format!("({} {})", if *op == b'a' { "car" } else { "cdr" }, expr);
+        }
+        Some(expr)
+    }
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Malformed(path) => write!(f, "{:?} is not c[ad]+r", path),
+            PathError::Step { path, error, .. } => {
+                write!(f, "{}: {}: {}", path, self.expression().unwrap(), error)
+            }
+        }
+    }
+}
+
+impl Error for PathError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PathError::Malformed(_) => None,
+            PathError::Step { error, .. } => Some(error),
+        }
+    }
+}
+
+// const, so that cxr! checks its literal when compiling
+pub const fn is_cxr(path: &str) -> bool {
+    let path = path.as_bytes();
+    if path.len() < 3 || path[0] != b'c' || path[path.len() - 1] != b'r' {
+        return false;
+    }
+    let mut i = 1;
+    while i < path.len() - 1 {
+        if path[i] != b'a' && path[i] != b'd' {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// the a's and d's in the order they are applied
+fn ops(path: &str) -> Result<Vec<u8>, PathError> {
+    if !is_cxr(path) {
+        return Err(PathError::Malformed(path.to_string()));
+    }
+    Ok(path[1..path.len() - 1].bytes().rev().collect())
+}
+
+impl ToOwned for dyn ConsAny {
+    type Owned = Box<dyn ConsAny>;
+
+    fn to_owned(&self) -> Box<dyn ConsAny> {
+        self.dyn_clone()
+    }
+}
+
+// None is the '() after the last pair
+fn step(x: Option<&dyn ConsAny>, op: u8) -> Result<Option<&dyn ConsAny>, ListError> {
+    let x = x.ok_or(ListError::Empty)?;
+    let list = x.cast_ref::<List>().ok_or_else(|| wrong_type::<List>(x))?;
+    let pair = list.as_cons().ok_or(ListError::Empty)?;
+    match op {
+        b'a' => pair.car_ref().ok_or(ListError::Taken).map(Some),
+        _ => Ok(pair.cdr_ref()),
+    }
+}
+
+// the walk leaves the list as it is, a missing cdr isn't filled in
+fn step_mut(x: &mut dyn ConsAny, op: u8) -> Result<&mut dyn ConsAny, ListError> {
+    let error = wrong_type::<List>(x);
+    let list = x.cast_mut::<List>().ok_or(error)?;
+    let pair = list.as_cons_mut().ok_or(ListError::Empty)?;
+    match op {
+        b'a' => pair.car_mut().ok_or(ListError::Taken),
+        _ => pair.cdr_mut().ok_or(ListError::NilCdr),
+    }
+}
+
+fn step_error(path: &str, step: usize, error: ListError) -> PathError {
+    PathError::Step {
+        path: path.to_string(),
+        step,
+        error,
+    }
+}
+
+fn downcast_error<T>(path: &str, found: &dyn ConsAny) -> PathError {
+    // only reached once the path has been walked, so it is well formed
+    let steps = path.len() - 2;
+    step_error(path, steps, wrong_type::<T>(found))
+}
+
+impl List {
+    // (caddr list) is list.path("caddr")
+    pub fn path(&self, path: &str) -> Result<Cow<'_, dyn ConsAny>, PathError> {
+        let mut x: Option<&dyn ConsAny> = Some(self);
+        for (i, op) in ops(path)?.into_iter().enumerate() {
+            x = step(x, op).map_err(|error| step_error(path, i, error))?;
+        }
+        Ok(match x {
+            Some(x) => Cow::Borrowed(x),
+            None => Cow::Owned(Box::new(List::nil()) as Box<dyn ConsAny>),
+        })
+    }
+
+    pub fn path_as<T: Clone + 'static>(&self, path: &str) -> Result<Cow<'_, T>, PathError> {
+        let x = self.path(path)?;
+        if x.cast_ref::<T>().is_none() {
+            return Err(downcast_error::<T>(path, &*x));
+        }
+        Ok(match x {
+            Cow::Borrowed(x) => Cow::Borrowed(x.cast_ref().unwrap()),
+            Cow::Owned(x) => Cow::Owned(*(x as Box<dyn Any>).downcast().unwrap()),
+        })
+    }
+
+    // fails where path would, and on a d past the last pair
+    pub fn path_mut(&mut self, path: &str) -> Result<&mut dyn ConsAny, PathError> {
+        self.path(path)?;
+        let mut x: &mut dyn ConsAny = self;
+        for (i, op) in ops(path)?.into_iter().enumerate() {
+            x = step_mut(x, op).map_err(|error| step_error(path, i, error))?;
+        }
+        Ok(x)
+    }
+
+    pub fn path_as_mut<T: 'static>(&mut self, path: &str) -> Result<&mut T, PathError> {
+        let x = self.path_mut(path)?;
+        let error = downcast_error::<T>(path, x);
+        x.cast_mut().ok_or(error)
+    }
+
+    // moves the element out of the list, the cdr at the end of a list
+    // is a new '()
+    pub fn into_path(mut self, path: &str) -> Result<Box<dyn ConsAny>, PathError> {
+        self.path(path)?;
+        let mut ops = ops(path)?;
+        let last = ops.pop().unwrap();
+        let mut x: &mut dyn ConsAny = &mut self;
+        // checked by path, every step before the last lands on a pair
+        for op in ops {
+            x = step_mut(x, op).unwrap();
+        }
+        let pair = x.cast_mut::<List>().unwrap().as_cons_mut().unwrap();
+        Ok(match last {
+            b'a' => pair.car().unwrap(),
+            _ => pair.cdr().unwrap_or_else(|| Box::new(List::nil())),
+        })
+    }
+
+    pub fn into_path_as<T: Clone + 'static>(self, path: &str) -> Result<T, PathError> {
+        self.path_as::<T>(path)?;
+        let x: Box<dyn Any> = self.into_path(path)?;
+        Ok(*x.downcast().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, match_list, quote, sym, utils::symbol::Symbol};
+
+    #[test]
+    fn borrow_and_mutate() {
+        let mut l = quote!((define (square x) (* x x)));
+        assert_eq!(cxr!(l, "car" as Symbol).as_deref(), Ok(&sym!(define)));
+        assert_eq!(cxr!(l, "caadr" as Symbol).as_deref(), Ok(&sym!(square)));
+        assert_eq!(*l.path("caddr").unwrap(), quote!((* x x)));
+        assert_eq!(*l.path("cdddr").unwrap(), List::nil());
+
+        *cxr!(mut l, "caddr" as List).unwrap() = quote!((expt x 2));
+        cxr!(mut l, "cdadr" as List).unwrap().set_car(sym!(y));
+        assert_eq!(l, quote!((define (square y) (expt x 2))));
+
+        let dotted = list!(1; 2);
+        assert_eq!(cxr!(dotted, "cdr" as i32).as_deref(), Ok(&2));
+    }
+
+    #[test]
+    fn own() {
+        let l = quote!((a (b c) d));
+        assert_eq!(cxr!(move l.clone(), "cadr" as List), Ok(quote!((b c))));
+        assert_eq!(*l.clone().into_path("cdddr").unwrap(), List::nil());
+        assert_eq!(cxr!(move l, "caddr" as Symbol), Ok(sym!(d)));
+    }
+
+    #[test]
+    fn failing_step() {
+        let mut l = list!(1, list!(2), "x");
+        let error = cxr!(l, "cadar").unwrap_err();
+        assert_eq!(error.step(), Some(1));
+        assert_eq!(error.expression().unwrap(), "(cdr (car list))");
+        assert_eq!(
+            error.to_string(),
+            "cadar: (cdr (car list)): expected app::utils::list::List, got i32"
+        );
+
+        let error = cxr!(l, "caddddr").unwrap_err();
+        assert_eq!(
+            error,
+            PathError::Step {
+                path: "caddddr".to_string(),
+                step: 3,
+                error: ListError::Empty
+            }
+        );
+        assert_eq!(cxr!(mut l, "caddddr").unwrap_err(), error);
+        assert_eq!(l, list!(1, list!(2), "x"));
+        let error = cxr!(l, "caddr" as i32).unwrap_err();
+        assert_eq!(error.step(), Some(3));
+        assert_eq!(error.expression().unwrap(), "(car (cdr (cdr list)))");
+        assert!(cxr!(move l, "cadadr" as i32).is_err());
+
+        let mut l = list!(1, 2);
+        l.cdr_mut().unwrap().car::<i32>();
+        let error = cxr!(l, "cadr").unwrap_err();
+        assert!(matches!(
+            error,
+            PathError::Step {
+                step: 1,
+                error: ListError::Taken,
+                ..
+            }
+        ));
+        assert_eq!(cxr!(mut l, "cadr").unwrap_err(), error);
+    }
+
+    #[test]
+    fn nil_tail() {
+        // the cdr of the last pair is a new '(), it can't be borrowed
+        // mutably and the list is left as it was
+        let mut l = list!(1, 2, 3);
+        let nil = cxr!(l, "cdddr").unwrap();
+        assert!(matches!(nil, Cow::Owned(_)));
+        assert_eq!(*nil, List::nil());
+        assert_eq!(cxr!(l, "cdddr" as List).as_deref(), Ok(&List::nil()));
+        assert_eq!(
+            cxr!(mut l, "cdddr" as List),
+            Err(PathError::Step {
+                path: "cdddr".to_string(),
+                step: 2,
+                error: ListError::NilCdr
+            })
+        );
+        let last = l.cdr_ref().unwrap().cdr_ref().unwrap();
+        assert!(last.as_cons().unwrap().cdr_ref().is_none());
+
+        let mut one = list!(1);
+        assert!(one.path_mut("cdr").is_err());
+        assert_eq!(match_list!(one, (x: i32) => *x, _ => 0), 1);
+    }
+
+    #[test]
+    fn bad_path() {
+        let mut l = list!(1);
+        let error = l.path("cxar").unwrap_err();
+        assert_eq!(error, PathError::Malformed("cxar".to_string()));
+        assert_eq!(error.to_string(), "\"cxar\" is not c[ad]+r");
+        assert_eq!((error.step(), error.expression()), (None, None));
+        assert!(l.path_mut("cr").is_err());
+        assert!(l.clone().into_path("car ").is_err());
+        assert!(l.path_as::<i32>("").is_err());
+    }
+}
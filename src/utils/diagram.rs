@@ -0,0 +1,495 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    cons::{Cons, ConsAny},
+    list::List,
+    pair_graph::{Datum, PairGraph, PairId},
+    printer::{as_pair, is_nil, write_atom},
+};
+
+// box-and-pointer diagrams, the pictures of SICP 2.2 and 3.3, as
+// Graphviz DOT or as ASCII art. every pair is drawn once, so a pair
+// that is reached twice, which only a PairGraph can have, is pointed
+// to from both places (DOT) or labeled like the printer does (ASCII):
+//
+// (1 (2) . 3)
+//
+// +---+---+    +---+---+
+// | * | *-+--->| * | *-+--> 3
+// +-|-+---+    +-|-+---+
+//   v            v
+//   1          +---+---+
+//              | * | / |
+//              +-|-+---+
+//                v
+//                2
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagram {
+    cells: Vec<Cell>,
+    root: Slot,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Cell {
+    car: Slot,
+    cdr: Slot,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Slot {
+    Nil,
+    Atom(String),
+    Cell(usize),
+}
+
+impl Diagram {
+    pub fn of_list(list: &List) -> Self {
+        let mut builder = Builder::default();
+        let root = match list.as_cons() {
+            Some(cons) => builder.add_pair(cons),
+            None => Slot::Nil,
+        };
+        builder.finish(root)
+    }
+
+    pub fn of_cons(cons: &Cons) -> Self {
+        let mut builder = Builder::default();
+        let root = builder.add_pair(cons);
+        builder.finish(root)
+    }
+
+    pub fn of_graph(graph: &PairGraph, datum: &Datum) -> Self {
+        let mut builder = GraphBuilder {
+            graph,
+            cells: vec![],
+            seen: HashMap::new(),
+        };
+        let root = builder.slot(datum);
+        Diagram {
+            cells: builder.cells,
+            root,
+        }
+    }
+
+    // cells with more than one pointer to them
+    fn shared(&self) -> HashSet<usize> {
+        let mut count = HashMap::new();
+        let slots = self.cells.iter().flat_map(|cell| [&cell.car, &cell.cdr]);
+        for slot in slots.chain([&self.root]) {
+            if let Slot::Cell(i) = slot {
+                *count.entry(*i).or_insert(0) += 1;
+            }
+        }
+        count
+            .into_iter()
+            .filter(|(_, n)| *n > 1)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph list {\n    node [shape=record];\n");
+        if self.root == Slot::Nil {
+            out.push_str("    nil [shape=plaintext, label=\"()\"];\n");
+        }
+        if let Slot::Atom(atom) = &self.root {
+            out.push_str(&format!(
+                "    atom [shape=plaintext, label=\"{}\"];\n",
+                escape(atom)
+            ));
+        }
+        let field = |slot: &Slot| match slot {
+            Slot::Nil => "/".to_string(),
+            Slot::Atom(atom) => escape(atom),
+            Slot::Cell(_) => " ".to_string(),
+        };
+        for (i, cell) in self.cells.iter().enumerate() {
+            out.push_str(&format!(
+                "    c{} [label=\"<car> {}|<cdr> {}\"];\n",
+                i,
+                field(&cell.car),
+                field(&cell.cdr)
+            ));
+        }
+        for (i, cell) in self.cells.iter().enumerate() {
+            for (port, slot) in [("car", &cell.car), ("cdr", &cell.cdr)] {
+                if let Slot::Cell(to) = slot {
+                    out.push_str(&format!("    c{}:{} -> c{};\n", i, port, to));
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_ascii(&self) -> String {
+        let root = match &self.root {
+            Slot::Nil => return "()\n".to_string(),
+            Slot::Atom(atom) => return format!("{}\n", atom),
+            Slot::Cell(root) => *root,
+        };
+        let mut ascii = Ascii {
+            diagram: self,
+            shared: self.shared(),
+            labels: HashMap::new(),
+            rows: vec![],
+        };
+        ascii.draw(0, 0, root);
+        let mut out = String::new();
+        for row in ascii.rows {
+            let row: String = row.into_iter().collect();
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// a nested List or Cons can't share pairs, the addresses are only
+// checked so that the builder works on any pair it is given
+#[derive(Default)]
+struct Builder {
+    cells: Vec<Cell>,
+    seen: HashMap<*const Cons, usize>,
+}
+
+impl Builder {
+    fn finish(self, root: Slot) -> Diagram {
+        Diagram {
+            cells: self.cells,
+            root,
+        }
+    }
+
+    fn slot(&mut self, atom: Option<&dyn ConsAny>) -> Slot {
+        match atom {
+            None => Slot::Nil,
+            Some(atom) if is_nil(atom) => Slot::Nil,
+            Some(atom) => match as_pair(atom) {
+                Some(cons) => self.add_pair(cons),
+                None => {
+                    let mut text = String::new();
+                    write_atom(&mut text, atom).unwrap();
+                    Slot::Atom(text)
+                }
+            },
+        }
+    }
+
+    // loops along the cdrs, recursing only into the cars
+    fn add_pair(&mut self, cons: &Cons) -> Slot {
+        if let Some(i) = self.seen.get(&(cons as *const Cons)) {
+            return Slot::Cell(*i);
+        }
+        let head = self.cells.len();
+        let (mut cons, mut prev) = (cons, None::<usize>);
+        loop {
+            let i = self.cells.len();
+            if let Some(prev) = prev {
+                self.cells[prev].cdr = Slot::Cell(i);
+            }
+            prev = Some(i);
+            self.seen.insert(cons, i);
+            self.cells.push(Cell {
+                car: Slot::Nil,
+                cdr: Slot::Nil,
+            });
+            self.cells[i].car = self.slot(cons.car_ref());
+            let next = cons.cdr_ref().and_then(as_pair);
+            match next {
+                Some(next) if !self.seen.contains_key(&(next as *const Cons)) => cons = next,
+                _ => {
+                    self.cells[i].cdr = self.slot(cons.cdr_ref());
+                    break;
+                }
+            }
+        }
+        Slot::Cell(head)
+    }
+}
+
+struct GraphBuilder<'a> {
+    graph: &'a PairGraph,
+    cells: Vec<Cell>,
+    seen: HashMap<PairId, usize>,
+}
+
+impl GraphBuilder<'_> {
+    fn slot(&mut self, datum: &Datum) -> Slot {
+        match datum {
+            Datum::Nil => Slot::Nil,
+            Datum::Atom(atom) => {
+                let mut text = String::new();
+                write_atom(&mut text, &**atom).unwrap();
+                Slot::Atom(text)
+            }
+            Datum::Pair(pair) => self.add_pair(*pair),
+        }
+    }
+
+    fn add_pair(&mut self, pair: PairId) -> Slot {
+        if let Some(i) = self.seen.get(&pair) {
+            return Slot::Cell(*i);
+        }
+        let head = self.cells.len();
+        let (mut pair, mut prev) = (pair, None::<usize>);
+        loop {
+            let i = self.cells.len();
+            if let Some(prev) = prev {
+                self.cells[prev].cdr = Slot::Cell(i);
+            }
+            prev = Some(i);
+            self.seen.insert(pair, i);
+            self.cells.push(Cell {
+                car: Slot::Nil,
+                cdr: Slot::Nil,
+            });
+            self.cells[i].car = self.slot(self.graph.car(pair));
+            match self.graph.cdr(pair).as_pair() {
+                Some(next) if !self.seen.contains_key(&next) => pair = next,
+                _ => {
+                    self.cells[i].cdr = self.slot(self.graph.cdr(pair));
+                    break;
+                }
+            }
+        }
+        Slot::Cell(head)
+    }
+}
+
+// DOT record fields treat these as markup
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if "\\\"{}|<> ".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+struct Ascii<'a> {
+    diagram: &'a Diagram,
+    shared: HashSet<usize>,
+    labels: HashMap<usize, usize>,
+    rows: Vec<Vec<char>>,
+}
+
+impl Ascii<'_> {
+    fn put(&mut self, x: usize, y: usize, text: &str) {
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, vec![]);
+        }
+        let row = &mut self.rows[y];
+        for (i, c) in text.chars().enumerate() {
+            if row.len() <= x + i {
+                row.resize(x + i + 1, ' ');
+            }
+            row[x + i] = c;
+        }
+    }
+
+    // what a pointer to slot shows instead of an arrow: an atom, or
+    // the label of a pair that is already drawn
+    fn text(&self, slot: &Slot) -> Option<String> {
+        match slot {
+            Slot::Atom(atom) => Some(atom.clone()),
+            Slot::Cell(i) => self.labels.get(i).map(|label| format!("#{}#", label)),
+            Slot::Nil => None,
+        }
+    }
+
+    // draws the list starting at cell with its first box at (x, y),
+    // returns how far right it reaches
+    fn draw(&mut self, x: usize, y: usize, cell: usize) -> usize {
+        let (mut x, mut cell) = (x, cell);
+        let mut right = x;
+        loop {
+            let Cell { car, cdr } = &self.diagram.cells[cell];
+            let label = self.shared.contains(&cell).then(|| {
+                let label = self.labels.len();
+                self.labels.insert(cell, label);
+                format!("#{}=", label)
+            });
+
+            self.put(x, y, "+---+---+");
+            let car_field = if *car == Slot::Nil { "| / " } else { "| * " };
+            let cdr_field = if *cdr == Slot::Nil { "| / |" } else { "| *-+" };
+            self.put(x, y + 1, &format!("{}{}", car_field, cdr_field));
+            self.put(
+                x,
+                y + 2,
+                if *car == Slot::Nil {
+                    "+---+---+"
+                } else {
+                    "+-|-+---+"
+                },
+            );
+            let mut width = 9;
+            if let Some(label) = &label {
+                self.put(x + 10, y, label);
+                width += label.len() + 1;
+            }
+
+            // the car hangs below the box
+            if *car != Slot::Nil {
+                self.put(x + 2, y + 3, "v");
+                let car_right = match self.text(car) {
+                    Some(text) => {
+                        self.put(x + 2, y + 4, &text);
+                        x + 2 + text.chars().count()
+                    }
+                    None => {
+                        let Slot::Cell(sub) = car else { unreachable!() };
+                        self.draw(x, y + 4, *sub)
+                    }
+                };
+                right = right.max(car_right);
+            }
+
+            match cdr {
+                Slot::Nil => return right.max(x + width),
+                Slot::Cell(next) if !self.labels.contains_key(next) => {
+                    let next_x = (x + width + 4).max(right + 4);
+                    let arrow = "-".repeat(next_x - x - 10) + ">";
+                    self.put(x + 9, y + 1, &arrow);
+                    right = right.max(next_x);
+                    (x, cell) = (next_x, *next);
+                }
+                _ => {
+                    let text = self.text(cdr).unwrap();
+                    self.put(x + 9, y + 1, &format!("--> {}", text));
+                    let end = x + 13 + text.chars().count();
+                    return right.max(end).max(x + width);
+                }
+            }
+        }
+    }
+}
+
+impl List {
+    pub fn to_dot(&self) -> String {
+        Diagram::of_list(self).to_dot()
+    }
+
+    pub fn to_ascii(&self) -> String {
+        Diagram::of_list(self).to_ascii()
+    }
+}
+
+impl Cons {
+    pub fn to_dot(&self) -> String {
+        Diagram::of_cons(self).to_dot()
+    }
+
+    pub fn to_ascii(&self) -> String {
+        Diagram::of_cons(self).to_ascii()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, quote};
+
+    fn lines(text: &str) -> String {
+        text.lines().map(|line| format!("{}\n", line)).collect()
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(list!().to_ascii(), "()\n");
+        assert_eq!(
+            list!(1, [2]; 3).to_ascii(),
+            lines(
+                "+---+---+    +---+---+
+| * | *-+--->| * | *-+--> 3
++-|-+---+    +-|-+---+
+  v            v
+  1          +---+---+
+             | * | / |
+             +-|-+---+
+               v
+               2"
+            )
+        );
+        assert_eq!(
+            list!([1, 2], [], "x").to_ascii(),
+            lines(
+                "+---+---+                 +---+---+    +---+---+
+| * | *-+---------------->| / | *-+--->| * | / |
++-|-+---+                 +---+---+    +-|-+---+
+  v                                      v
++---+---+    +---+---+                   \"x\"
+| * | *-+--->| * | / |
++-|-+---+    +-|-+---+
+  v            v
+  1            2"
+            )
+        );
+    }
+
+    #[test]
+    fn ascii_shared() {
+        let mut g = PairGraph::new();
+        let z = g.add_list(&quote!((a b c)));
+        g.make_cycle(z.as_pair().unwrap());
+        assert_eq!(
+            Diagram::of_graph(&g, &z).to_ascii(),
+            lines(
+                "+---+---+ #0=    +---+---+    +---+---+
+| * | *-+------->| * | *-+--->| * | *-+--> #0#
++-|-+---+        +-|-+---+    +-|-+---+
+  v                v            v
+  a                b            c"
+            )
+        );
+
+        // (define x (list 'a)) (cons x x)
+        let x = g.add_list(&quote!((a)));
+        let y = Datum::from(g.cons(x.clone(), x));
+        assert_eq!(
+            Diagram::of_graph(&g, &y).to_ascii(),
+            lines(
+                "+---+---+
+| * | *-+--> #0#
++-|-+---+
+  v
++---+---+ #0=
+| * | / |
++-|-+---+
+  v
+  a"
+            )
+        );
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(
+            list!([1], "a b"; 2.5).to_dot(),
+            lines(
+                r#"digraph list {
+    node [shape=record];
+    c0 [label="<car>  |<cdr>  "];
+    c1 [label="<car> 1|<cdr> /"];
+    c2 [label="<car> \"a\ b\"|<cdr> 2.5"];
+    c0:car -> c1;
+    c0:cdr -> c2;
+}"#
+            )
+        );
+        assert_eq!(
+            list!().to_dot(),
+            "digraph list {\n    node [shape=record];\n    nil [shape=plaintext, label=\"()\"];\n}\n"
+        );
+
+        let mut g = PairGraph::new();
+        let x = g.add_list(&quote!((a)));
+        let y = Datum::from(g.cons(x.clone(), x));
+        let dot = Diagram::of_graph(&g, &y).to_dot();
+        assert!(dot.contains("c0:car -> c1;\n    c0:cdr -> c1;\n"));
+        assert_eq!(dot.matches("[label=").count(), 2);
+    }
+}
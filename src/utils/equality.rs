@@ -0,0 +1,155 @@
+use std::ptr;
+
+use num::rational::Rational64;
+
+use super::{cons::ConsAny, list::List, symbol::Symbol};
+
+// the three equality predicates of scheme, from the strictest:
+//
+// (eq? a b)    the same object. pairs, strings, floats and other
+//              atoms compare by address; symbols, booleans, chars and
+//              integers by value, like immediates in a scheme runtime
+// (eqv? a b)   eq?, or numbers of the same type and value
+// (equal? a b) same structure, PartialEq of the elements
+pub fn eq(a: &dyn ConsAny, b: &dyn ConsAny) -> bool {
+    if is_nil(a) && is_nil(b) {
+        return true;
+    }
+    if let Some(same) = immediate_eq(a, b) {
+        return same;
+    }
+    ptr::eq(address(a), address(b))
+}
+
+pub fn eqv(a: &dyn ConsAny, b: &dyn ConsAny) -> bool {
+    if eq(a, b) {
+        return true;
+    }
+    macro_rules! number {
+        ($($t:ty),*) => {
+            $(
+                if let (Some(x), Some(y)) = (a.cast_ref::<$t>(), b.cast_ref::<$t>()) {
+                    return x == y;
+                }
+            )*
+        };
+    }
+    number!(f32, f64, Rational64);
+    false
+}
+
+pub fn equal(a: &dyn ConsAny, b: &dyn ConsAny) -> bool {
+    a.dyn_eq(b)
+}
+
+fn is_nil(atom: &dyn ConsAny) -> bool {
+    atom.cast_ref::<List>().is_some_and(List::is_null)
+}
+
+fn address(atom: &dyn ConsAny) -> *const () {
+    atom as *const dyn ConsAny as *const ()
+}
+
+// Some when both are the same immediate type
+fn immediate_eq(a: &dyn ConsAny, b: &dyn ConsAny) -> Option<bool> {
+    macro_rules! immediate {
+        ($($t:ty),*) => {
+            $(
+                if let (Some(x), Some(y)) = (a.cast_ref::<$t>(), b.cast_ref::<$t>()) {
+                    return Some(x == y);
+                }
+            )*
+        };
+    }
+    immediate!(Symbol, bool, char);
+    immediate!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    None
+}
+
+impl List {
+    // (eq? list x), (eqv? list x) and (equal? list x)
+    pub fn is_eq(&self, x: &dyn ConsAny) -> bool {
+        eq(self, x)
+    }
+
+    pub fn is_eqv(&self, x: &dyn ConsAny) -> bool {
+        eqv(self, x)
+    }
+
+    pub fn is_equal(&self, x: &dyn ConsAny) -> bool {
+        equal(self, x)
+    }
+
+    // (memq x list): the sublist starting at the first element eq? to x
+    pub fn memq(&self, x: &dyn ConsAny) -> Option<&List> {
+        self.member_by(x, eq)
+    }
+
+    pub fn memv(&self, x: &dyn ConsAny) -> Option<&List> {
+        self.member_by(x, eqv)
+    }
+
+    pub fn member(&self, x: &dyn ConsAny) -> Option<&List> {
+        self.member_by(x, equal)
+    }
+
+    fn member_by(
+        &self,
+        x: &dyn ConsAny,
+        same: fn(&dyn ConsAny, &dyn ConsAny) -> bool,
+    ) -> Option<&List> {
+        let mut list = self;
+        loop {
+            if same(list.as_cons()?.car_ref()?, x) {
+                return Some(list);
+            }
+            list = list.cdr_ref()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, quote, sym};
+
+    #[test]
+    fn scheme_equality() {
+        let l = quote!((a b c));
+        assert!(eq(&l, &l));
+        assert!(!eq(&l, &l.clone()));
+        assert!(eqv(&l, &l) && !eqv(&l, &l.clone()));
+        assert!(equal(&l, &l.clone()));
+        assert!(eq(&List::nil(), &List::nil()));
+        assert!(l.is_eq(&l) && l.is_eqv(&l));
+        assert!(!l.is_eq(&l.clone()) && !l.is_eqv(&l.clone()));
+        assert!(l.is_equal(&l.clone()) && !l.is_equal(&quote!((a b))));
+        assert!(List::nil().is_eq(&List::nil()) && !l.is_equal(&sym!(a)));
+
+        assert!(eq(&sym!(a), &Symbol::new("a")));
+        assert!(eq(&1, &1) && !eq(&1, &1i64));
+        let (x, y) = (2.0, 2.0);
+        assert!(!eq(&x, &y) && eqv(&x, &y));
+        let (half, other) = (Rational64::new(1, 2), Rational64::new(2, 4));
+        assert!(!eq(&half, &other) && eqv(&half, &other));
+        assert!(!eqv(&half, &0.5));
+        let s = "abc".to_string();
+        assert!(eq(&s, &s));
+        assert!(!eqv(&s, &"abc".to_string()) && equal(&s, &"abc".to_string()));
+    }
+
+    #[test]
+    fn test_2_54() {
+        assert!(quote!((pear banana prune)).memq(&sym!(apple)).is_none());
+        let l = quote!((x (apple sauce) y apple pear));
+        assert_eq!(l.memq(&sym!(apple)), Some(&quote!((apple pear))));
+
+        // a list is only eq? to itself, member compares with equal?
+        let l = list!(1.5, quote!((a b)), 3);
+        assert!(l.memq(&quote!((a b))).is_none());
+        assert_eq!(l.member(&quote!((a b))).unwrap().len(), 2);
+        assert!(l.memq(&1.5).is_none());
+        assert_eq!(l.memv(&1.5), Some(&l));
+        assert_eq!(l.memq(&3), Some(&list!(3)));
+    }
+}
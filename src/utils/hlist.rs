@@ -0,0 +1,325 @@
+use super::{cons::ConsAny, list::List};
+
+// a list whose length and element types are part of its type, for
+// data with a fixed shape. the dynamic List boxes every element and
+// downcasts it back on the way out, an HList checks it all when
+// compiling, so car and cdr can't fail and need no type annotation
+//
+// hlist![1, "two", 3.0]               HList![i32, &str, f64]
+// hlist![1, "two", 3.0].cdr().car()   &"two"
+// hlist![].car()                      doesn't compile, () has no car
+//
+// map and fold take a Poly, a function that is implemented for each
+// element type it is called on, since a closure only has one argument
+// type. List::from turns an HList into a List, an HList element stays
+// one opaque atom, convert it first for a nested List
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct HNil;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct HCons<H, T> {
+    pub head: H,
+    pub tail: T,
+}
+
+#[macro_export]
+macro_rules! hlist {
+    () => {
+        $crate::utils::hlist::HNil
+    };
+    ($head:expr $(, $tail:expr)* $(,)?) => {
+        $crate::utils::hlist::HCons {
+            head: $head,
+            tail: $crate::hlist!($($tail),*),
+        }
+    };
+}
+
+// the type of hlist![..]
+#[macro_export]
+macro_rules! HList {
+    () => {
+        $crate::utils::hlist::HNil
+    };
+    ($head:ty $(, $tail:ty)* $(,)?) => {
+        $crate::utils::hlist::HCons<$head, $crate::HList!($($tail),*)>
+    };
+}
+
+pub trait HList: Sized {
+    const LEN: usize;
+
+    fn len(&self) -> usize {
+        Self::LEN
+    }
+
+    fn is_empty(&self) -> bool {
+        Self::LEN == 0
+    }
+
+    // (cons head self)
+    fn prepend<H>(self, head: H) -> HCons<H, Self> {
+        HCons { head, tail: self }
+    }
+}
+
+impl HList for HNil {
+    const LEN: usize = 0;
+}
+
+impl<H, T: HList> HList for HCons<H, T> {
+    const LEN: usize = 1 + T::LEN;
+}
+
+impl<H, T: HList> HCons<H, T> {
+    pub fn car(&self) -> &H {
+        &self.head
+    }
+
+    pub fn cdr(&self) -> &T {
+        &self.tail
+    }
+
+    pub fn car_mut(&mut self) -> &mut H {
+        &mut self.head
+    }
+
+    pub fn cdr_mut(&mut self) -> &mut T {
+        &mut self.tail
+    }
+
+    pub fn into_parts(self) -> (H, T) {
+        (self.head, self.tail)
+    }
+}
+
+// a function with one impl per argument type
+pub trait Poly<A> {
+    type Output;
+
+    fn call(&mut self, a: A) -> Self::Output;
+}
+
+pub trait HMap<F> {
+    type Output: HList;
+
+    fn map(self, f: &mut F) -> Self::Output;
+}
+
+impl<F> HMap<F> for HNil {
+    type Output = HNil;
+
+    fn map(self, _: &mut F) -> HNil {
+        HNil
+    }
+}
+
+impl<F: Poly<H>, H, T: HMap<F>> HMap<F> for HCons<H, T> {
+    type Output = HCons<F::Output, T::Output>;
+
+    fn map(self, f: &mut F) -> Self::Output {
+        HCons {
+            head: f.call(self.head),
+            tail: self.tail.map(f),
+        }
+    }
+}
+
+// front to back, f is called as a Poly on (acc, element)
+pub trait HFold<Acc, F> {
+    fn fold(self, init: Acc, f: &mut F) -> Acc;
+}
+
+impl<Acc, F> HFold<Acc, F> for HNil {
+    fn fold(self, init: Acc, _: &mut F) -> Acc {
+        init
+    }
+}
+
+impl<Acc, F: Poly<(Acc, H), Output = Acc>, H, T: HFold<Acc, F>> HFold<Acc, F> for HCons<H, T> {
+    fn fold(self, init: Acc, f: &mut F) -> Acc {
+        let acc = f.call((init, self.head));
+        self.tail.fold(acc, f)
+    }
+}
+
+pub trait HAppend<R> {
+    type Output: HList;
+
+    fn append(self, right: R) -> Self::Output;
+}
+
+impl<R: HList> HAppend<R> for HNil {
+    type Output = R;
+
+    fn append(self, right: R) -> R {
+        right
+    }
+}
+
+impl<H, T: HAppend<R>, R> HAppend<R> for HCons<H, T> {
+    type Output = HCons<H, T::Output>;
+
+    fn append(self, right: R) -> Self::Output {
+        HCons {
+            head: self.head,
+            tail: self.tail.append(right),
+        }
+    }
+}
+
+// the elements of self reversed in front of acc, like the iterative
+// reverse of SICP 2.18
+pub trait HReverseOnto<Acc> {
+    type Output: HList;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output;
+}
+
+impl<Acc: HList> HReverseOnto<Acc> for HNil {
+    type Output = Acc;
+
+    fn reverse_onto(self, acc: Acc) -> Acc {
+        acc
+    }
+}
+
+impl<Acc, H, T: HReverseOnto<HCons<H, Acc>>> HReverseOnto<Acc> for HCons<H, T> {
+    type Output = T::Output;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output {
+        self.tail.reverse_onto(HCons {
+            head: self.head,
+            tail: acc,
+        })
+    }
+}
+
+pub trait HReverse {
+    type Output: HList;
+
+    fn reverse(self) -> Self::Output;
+}
+
+impl<L: HReverseOnto<HNil>> HReverse for L {
+    type Output = L::Output;
+
+    fn reverse(self) -> Self::Output {
+        self.reverse_onto(HNil)
+    }
+}
+
+impl From<HNil> for List {
+    fn from(_: HNil) -> Self {
+        List::nil()
+    }
+}
+
+impl<H: ConsAny, T> From<HCons<H, T>> for List
+where
+    List: From<T>,
+{
+    fn from(hlist: HCons<H, T>) -> Self {
+        List::cons(hlist.head, List::from(hlist.tail))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{hlist, list, HList};
+
+    #[test]
+    fn car_cdr() {
+        let mut h: HList![i32, &str, f64] = hlist![1, "two", 3.0];
+        assert_eq!(h.len(), 3);
+        assert!(hlist![].is_empty());
+        assert_eq!(h.car(), &1);
+        assert_eq!(h.cdr().car(), &"two");
+        *h.cdr_mut().cdr_mut().car_mut() += 1.5;
+        assert_eq!(h.cdr().cdr().car(), &4.5);
+        let (one, rest) = h.into_parts();
+        assert_eq!((one, rest.prepend('a')), (1, hlist!['a', "two", 4.5]));
+    }
+
+    // to string, and doubles the numbers on the way
+    struct Show;
+
+    impl Poly<i32> for Show {
+        type Output = String;
+
+        fn call(&mut self, a: i32) -> String {
+            (a * 2).to_string()
+        }
+    }
+
+    impl Poly<&str> for Show {
+        type Output = String;
+
+        fn call(&mut self, a: &str) -> String {
+            a.to_string()
+        }
+    }
+
+    impl Poly<f64> for Show {
+        type Output = f64;
+
+        fn call(&mut self, a: f64) -> f64 {
+            a * 2.0
+        }
+    }
+
+    // adds up the numbers, counts anything else as 1
+    struct Sum;
+
+    impl Poly<(f64, i32)> for Sum {
+        type Output = f64;
+
+        fn call(&mut self, (acc, a): (f64, i32)) -> f64 {
+            acc + a as f64
+        }
+    }
+
+    impl Poly<(f64, f64)> for Sum {
+        type Output = f64;
+
+        fn call(&mut self, (acc, a): (f64, f64)) -> f64 {
+            acc + a
+        }
+    }
+
+    impl Poly<(f64, &str)> for Sum {
+        type Output = f64;
+
+        fn call(&mut self, (acc, _): (f64, &str)) -> f64 {
+            acc + 1.0
+        }
+    }
+
+    #[test]
+    fn map_fold() {
+        let h = hlist![1, "two", 3.0];
+        let shown: HList![String, String, f64] = h.map(&mut Show);
+        assert_eq!(shown, hlist!["2".to_string(), "two".to_string(), 6.0]);
+        assert_eq!(h.fold(0.0, &mut Sum), 5.0);
+        assert_eq!(hlist![].fold(0.0, &mut Sum), 0.0);
+    }
+
+    #[test]
+    fn append_reverse() {
+        let h = hlist![1, "two"].append(hlist![3.0, 'c']);
+        assert_eq!(h, hlist![1, "two", 3.0, 'c']);
+        let r: HList![char, f64, &str, i32] = h.reverse();
+        assert_eq!(r, hlist!['c', 3.0, "two", 1]);
+        assert_eq!(hlist![].append(hlist![1]).reverse(), hlist![1]);
+        assert_eq!(HList::len(&r.append(r)), 8);
+    }
+
+    #[test]
+    fn to_list() {
+        assert_eq!(List::from(hlist![1, "two", 3.0]), list!(1, "two", 3.0));
+        assert_eq!(List::from(hlist![]), list!());
+        let inner = List::from(hlist![2, 3]);
+        assert_eq!(List::from(hlist![1, inner]).to_string(), "(1 (2 3))");
+    }
+}
@@ -1,35 +1,119 @@
-use std::{any::Any, fmt::Debug, marker::PhantomData, mem};
+use std::{
+    any::{type_name, Any},
+    error::Error,
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+    mem,
+};
+
+use super::cons::{into_box, Cons, ConsAny};
+
+// a List is either the empty list '() or a pair whose cdr is another List
+#[derive(PartialEq)]
+pub struct List {
+    head: Option<Cons>,
+}
 
-use super::cons::{Cons, ConsAny, Link};
+// why a typed access to a List failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListError {
+    // car or cdr of '()
+    Empty,
+    // the car of a pair that was moved out with car()
+    Taken,
+    // a mutable borrow of the '() after the last pair, which isn't
+    // stored anywhere
+    NilCdr,
+    WrongType {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    // the element at index, from iter_downcast or try_as
+    Element {
+        index: usize,
+        expected: &'static str,
+        actual: &'static str,
+    },
+    // try_as on (... . tail)
+    Improper {
+        tail: &'static str,
+    },
+}
 
-#[derive(PartialEq, Debug)]
-pub struct List {
-    head: Cons,
+impl Display for ListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListError::Empty => write!(f, "expected a pair, got '()"),
+            ListError::Taken => write!(f, "the car was taken out of the pair"),
+            ListError::NilCdr => write!(f, "the '() after the last pair can't be borrowed mutably"),
+            ListError::WrongType { expected, actual } => {
+                write!(f, "expected {}, got {}", expected, actual)
+            }
+            ListError::Element {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "element {}: expected {}, got {}",
+                index, expected, actual
+            ),
+            ListError::Improper { tail } => {
+                write!(f, "expected a proper list, got a dotted tail of {}", tail)
+            }
+        }
+    }
+}
+
+impl Error for ListError {}
+
+pub(crate) fn wrong_type<T>(found: &dyn ConsAny) -> ListError {
+    ListError::WrongType {
+        expected: type_name::<T>(),
+        actual: found.type_name(),
+    }
+}
+
+fn wrong_element<T>(index: usize, found: &dyn ConsAny) -> ListError {
+    ListError::Element {
+        index,
+        expected: type_name::<T>(),
+        actual: found.type_name(),
+    }
 }
 
 impl List {
-    pub fn new<T: ConsAny>(mut car: T) -> Self {
-        // here need to handle if T is Box<dyn ConsAny>
-        // because if that, the result will be Box<Box<dyn ConsAny>>
-        // which will cause weird behavior, such as dyn_eq fail for T & Box<T>
-        if car
-            .as_ref_any()
-            .downcast_ref::<Box<dyn ConsAny>>()
-            .is_some()
-        {
-            let a = *(Box::new(car) as Box<dyn Any>)
-                .downcast::<Box<dyn ConsAny>>()
-                .unwrap();
-            return Self {
-                head: Cons::new(Some(a), None),
-            };
-        }
+    pub fn new<T: ConsAny>(car: T) -> Self {
         Self {
-            head: Cons::new(Some(Box::new(car)), None),
+            head: Some(Cons::new(Some(into_box(car)), None)),
         }
     }
 
+    // the empty list '()
+    pub const fn nil() -> Self {
+        Self { head: None }
+    }
+
+    // (cons car cdr)
+    pub fn cons<T: ConsAny>(car: T, cdr: List) -> Self {
+        let mut list = Self::new(car);
+        list.set_cdr(Some(cdr));
+        list
+    }
+
+    // (null? list)
+    pub fn is_null(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_null()
+    }
+
     pub fn len(&self) -> usize {
+        if self.is_null() {
+            return 0;
+        }
         let mut len = 1;
         let mut list = self;
         while list.cdr_ref().is_some() {
@@ -39,38 +123,107 @@ impl List {
         len
     }
 
+    pub(crate) fn as_cons(&self) -> Option<&Cons> {
+        self.head.as_ref()
+    }
+
+    pub(crate) fn as_cons_mut(&mut self) -> Option<&mut Cons> {
+        self.head.as_mut()
+    }
+
     pub fn take(&mut self) -> Self {
-        mem::replace(self, Self::default())
+        mem::take(self)
     }
 
     pub fn car<T: ConsAny + 'static>(&mut self) -> Option<T> {
-        self.head.car_downcast().map(|car| *car)
+        self.head.as_mut()?.car_downcast().map(|car| *car)
     }
 
     pub fn car_ref<T: ConsAny + 'static>(&self) -> Option<&T> {
-        self.head.car_downcast_ref()
+        self.head.as_ref()?.car_downcast_ref()
     }
 
     pub fn car_mut<T: ConsAny + 'static>(&mut self) -> Option<&mut T> {
-        self.head.car_downcast_mut()
+        self.head.as_mut()?.car_downcast_mut()
+    }
+
+    // the try_ accessors tell '() apart from an element of another type
+    pub fn try_car<T: ConsAny + 'static>(&mut self) -> Result<T, ListError> {
+        self.try_car_ref::<T>()?;
+        Ok(self.car().unwrap())
     }
 
+    pub fn try_car_ref<T: ConsAny + 'static>(&self) -> Result<&T, ListError> {
+        let head = self.head.as_ref().ok_or(ListError::Empty)?;
+        let car = head.car_ref().ok_or(ListError::Taken)?;
+        car.cast_ref().ok_or_else(|| wrong_type::<T>(car))
+    }
+
+    pub fn try_car_mut<T: ConsAny + 'static>(&mut self) -> Result<&mut T, ListError> {
+        self.try_car_ref::<T>()?;
+        Ok(self.car_mut().unwrap())
+    }
+
+    // Ok('()) at the end of the list, a dotted tail is an error
+    // and is left in place
+    pub fn try_cdr(&mut self) -> Result<Self, ListError> {
+        let head = self.head.as_mut().ok_or(ListError::Empty)?;
+        match head.cdr_ref() {
+            None => Ok(Self::nil()),
+            Some(cdr) if cdr.cast_ref::<Self>().is_none() => Err(wrong_type::<Self>(cdr)),
+            Some(_) => Ok(*head.cdr_downcast().unwrap()),
+        }
+    }
+
+    pub fn try_cdr_ref(&self) -> Result<Option<&Self>, ListError> {
+        let head = self.head.as_ref().ok_or(ListError::Empty)?;
+        match head.cdr_ref() {
+            None => Ok(None),
+            Some(cdr) => cdr
+                .cast_ref()
+                .map(Some)
+                .ok_or_else(|| wrong_type::<Self>(cdr)),
+        }
+    }
+
+    // checks every element once, the view then hands out &T's
+    pub fn try_as<T: 'static>(&self) -> Result<TypedList<'_, T>, ListError> {
+        if let Some(tail) = self.dotted_tail() {
+            return Err(ListError::Improper {
+                tail: tail.type_name(),
+            });
+        }
+        for item in self.iter_downcast::<T>() {
+            item?;
+        }
+        Ok(TypedList {
+            list: self,
+            marker: PhantomData,
+        })
+    }
+
+    // panics on '(), like set-car! on a non pair
     pub fn set_car<T: ConsAny + 'static>(&mut self, car: T) {
-        self.head.set_car(Some(car));
+        self.pair_mut("set_car").set_car(Some(car));
     }
 
+    // None when self is '() or its cdr is '(), or when the cdr is
+    // not a List (see dotted_tail), which is then left in place
     pub fn cdr(&mut self) -> Option<Self> {
-        self.head.cdr_downcast().map(|cdr| *cdr)
+        let head = self.head.as_mut()?;
+        head.cdr_downcast_ref::<Self>()?;
+        head.cdr_downcast().map(|cdr| *cdr)
     }
 
     pub fn cdr_ref(&self) -> Option<&Self> {
-        self.head.cdr_downcast_ref()
+        self.head.as_ref()?.cdr_downcast_ref()
     }
 
     pub fn cdr_mut(&mut self) -> Option<&mut Self> {
-        self.head.cdr_downcast_mut()
+        self.head.as_mut()?.cdr_downcast_mut()
     }
 
+    // last pair of the list, '() is its own tail
     pub fn tail(&mut self) -> &mut Self {
         let mut list = self;
         while list.cdr_mut().is_some() {
@@ -79,18 +232,58 @@ impl List {
         list
     }
 
+    // relinks the pairs in place. panics on an improper list
     pub fn reverse(mut self) -> Self {
-        if self.cdr_ref().is_none() {
-            return self;
+        assert!(self.is_proper(), "reverse: improper list");
+        let mut reversed = Self::nil();
+        while !self.is_null() {
+            let rest = self.cdr().unwrap_or_default();
+            self.set_cdr(Some(reversed));
+            reversed = self;
+            self = rest;
         }
-        let next = self.cdr().unwrap();
-        let mut head = next.reverse();
-        head.tail().set_cdr(Some(self));
-        head
+        reversed
     }
 
+    // final cdr of an improper list, e.g. 3 in (1 2 . 3), None for a proper list
+    pub fn dotted_tail(&self) -> Option<&dyn ConsAny> {
+        let mut list = self;
+        while let Some(next) = list.cdr_ref() {
+            list = next;
+        }
+        list.head
+            .as_ref()?
+            .cdr_ref()
+            .filter(|cdr| cdr.cast_ref::<Self>().is_none())
+    }
+
+    pub fn is_proper(&self) -> bool {
+        self.dotted_tail().is_none()
+    }
+
+    // set the cdr of the last pair, making the list improper unless tail
+    // is a List, then it's appended as is: (1 . (2 3)) is (1 2 3).
+    // returns the previous dotted tail. panics on '()
+    pub fn set_dotted_tail<T: ConsAny>(&mut self, tail: T) -> Option<Box<dyn ConsAny>> {
+        let old = self.take_dotted_tail();
+        let tail = into_box(tail);
+        if tail.cast_ref::<Self>().is_none_or(|tail| !tail.is_null()) {
+            self.tail().pair_mut("set_dotted_tail").set_cdr(Some(tail));
+        }
+        old
+    }
+
+    // make an improper list proper by removing its dotted tail
+    pub fn take_dotted_tail(&mut self) -> Option<Box<dyn ConsAny>> {
+        self.dotted_tail()?;
+        self.tail().head.as_mut()?.cdr()
+    }
+
+    // panics on '(), like set-cdr! on a non pair.
+    // setting '() is the same as setting None, the list ends here
     pub fn set_cdr(&mut self, cdr: Option<Self>) -> Option<Self> {
-        let a = self.head.set_cdr(cdr);
+        let cdr = cdr.filter(|cdr| !cdr.is_null());
+        let a = self.pair_mut("set_cdr").set_cdr(cdr);
         match a {
             Some(list) => match (list as Box<dyn Any>).downcast::<Self>() {
                 Ok(list) => Some(*list),
@@ -100,33 +293,39 @@ impl List {
         }
     }
 
+    fn pair_mut(&mut self, op: &str) -> &mut Cons {
+        match &mut self.head {
+            Some(head) => head,
+            None => panic!("{}: expected a pair, got '()", op),
+        }
+    }
+
     pub fn iter(&self) -> Iter<'_> {
         Iter {
-            car: self.head.car,
-            cdr: self.head.cdr,
-            marker: PhantomData,
+            next: self.as_cons(),
+            tail: None,
         }
     }
 
     pub fn iter_downcast<T>(&self) -> IterDowncast<'_, T> {
         IterDowncast {
-            car: self.head.car,
-            cdr: self.head.cdr,
+            iter: self.iter(),
+            index: 0,
             marker: PhantomData,
         }
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_> {
         IterMut {
-            car: self.head.car,
-            cdr: self.head.cdr,
-            marker: PhantomData,
+            next: self.as_cons_mut(),
+            tail: None,
         }
     }
 
     pub fn into_iter_downcast<T>(self) -> IntoIterDowncast<T> {
         IntoIterDowncast {
             next: self,
+            index: 0,
             marker: PhantomData,
         }
     }
@@ -134,15 +333,17 @@ impl List {
 
 impl Clone for List {
     fn clone(&self) -> Self {
-        self.iter().map(|item| item.dyn_clone()).collect()
+        let mut list: Self = self.iter().map(|item| item.dyn_clone()).collect();
+        if let Some(tail) = self.dotted_tail() {
+            list.set_dotted_tail(tail.dyn_clone());
+        }
+        list
     }
 }
 
 impl Default for List {
     fn default() -> Self {
-        Self {
-            head: Cons::default(),
-        }
+        Self::nil()
     }
 }
 
@@ -151,7 +352,10 @@ impl IntoIterator for List {
     type Item = Box<dyn ConsAny>;
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter { next: self }
+        IntoIter {
+            next: self,
+            tail: None,
+        }
     }
 }
 
@@ -175,15 +379,24 @@ impl<'a> IntoIterator for &'a mut List {
 
 impl<T: ConsAny> FromIterator<T> for List {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut dummy = Self::new(0);
-        dummy.extend(iter);
-        dummy.cdr().unwrap()
+        let mut list = Self::nil();
+        list.extend(iter);
+        list
     }
 }
 
+// panics on an improper list, there is no end to append to
 impl<T: ConsAny> Extend<T> for List {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut iter = iter.into_iter();
+        if self.is_null() {
+            match iter.next() {
+                Some(first) => *self = Self::new(first),
+                None => return,
+            }
+        }
         let mut tail = self.tail();
+        assert!(tail.is_proper(), "extend: improper list");
         let mut new_tail: List;
         for mut item in iter {
             new_tail = Self::new(item);
@@ -193,169 +406,274 @@ impl<T: ConsAny> Extend<T> for List {
     }
 }
 
+fn into_list(item: Box<dyn ConsAny>) -> Result<List, Box<dyn ConsAny>> {
+    if item.cast_ref::<List>().is_some() {
+        return Ok(*(item as Box<dyn Any>).downcast::<List>().unwrap());
+    }
+    Err(item)
+}
+
+// an element of an improper list, every car then the final cdr
+#[derive(Debug, PartialEq)]
+pub enum Element<T> {
+    Car(T),
+    Tail(T),
+}
+
+// iterators over a List that can hand out the dotted tail they
+// stopped at, once they're exhausted
+pub trait DottedIterator: Iterator + Sized {
+    fn take_tail(&mut self) -> Option<Self::Item>;
+
+    // yield every car as Element::Car, then the dotted tail, if any,
+    // as Element::Tail
+    fn dotted(self) -> Dotted<Self> {
+        Dotted { iter: self }
+    }
+}
+
 #[derive(Debug)]
+pub struct Dotted<I> {
+    iter: I,
+}
+
+impl<I: DottedIterator> Iterator for Dotted<I> {
+    type Item = Element<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(car) => Some(Element::Car(car)),
+            None => self.iter.take_tail().map(Element::Tail),
+        }
+    }
+}
 
+#[derive(Debug)]
 pub struct Iter<'a> {
-    car: Link,
-    cdr: Link,
-    marker: PhantomData<&'a dyn ConsAny>,
+    next: Option<&'a Cons>,
+    tail: Option<&'a dyn ConsAny>,
 }
 
 impl<'a> Iterator for Iter<'a> {
     type Item = &'a dyn ConsAny;
     fn next(&mut self) -> Option<Self::Item> {
-        let cur = self.car.map(|car| unsafe { &*car.as_ptr() });
-        match self.cdr {
-            Some(cdr) => unsafe {
-                match (&*cdr.as_ptr()).as_ref_any().downcast_ref::<List>() {
-                    Some(next) => {
-                        self.car = next.head.car;
-                        self.cdr = next.head.cdr;
-                        return cur;
-                    }
-                    None => {}
-                }
-            },
-            None => {}
-        };
-        self.car = None;
-        self.cdr = None;
-        cur
+        let cons = self.next.take()?;
+        let car = cons.car_ref()?;
+        if let Some(cdr) = cons.cdr_ref() {
+            match cdr.cast_ref::<List>() {
+                Some(list) => self.next = list.as_cons(),
+                None => self.tail = Some(cdr),
+            }
+        }
+        Some(car)
     }
 }
 
-#[derive(Debug)]
+impl DottedIterator for Iter<'_> {
+    fn take_tail(&mut self) -> Option<Self::Item> {
+        self.tail.take()
+    }
+}
 
+// yields an error for an element that is not a T and goes on
+#[derive(Debug)]
 pub struct IterDowncast<'a, T> {
-    car: Link,
-    cdr: Link,
+    iter: Iter<'a>,
+    index: usize,
     marker: PhantomData<&'a T>,
 }
 
 impl<'a, T: 'static> Iterator for IterDowncast<'a, T> {
-    type Item = &'a T;
+    type Item = Result<&'a T, ListError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let cur = match self.car {
-            Some(car) => unsafe { &*car.as_ptr() }.as_ref_any().downcast_ref::<T>(),
-            None => None,
-        };
-
-        match self.cdr {
-            Some(cdr) => unsafe {
-                match (&*cdr.as_ptr()).as_ref_any().downcast_ref::<List>() {
-                    Some(next) => {
-                        self.car = next.head.car;
-                        self.cdr = next.head.cdr;
-                        return cur;
-                    }
-                    None => {}
-                }
-            },
-            None => {}
-        };
-        self.car = None;
-        self.cdr = None;
-        cur
+        let item = self.iter.next()?;
+        self.index += 1;
+        Some(
+            item.cast_ref()
+                .ok_or_else(|| wrong_element::<T>(self.index - 1, item)),
+        )
     }
 }
 
 #[derive(Debug)]
 pub struct IterMut<'a> {
-    car: Link,
-    cdr: Link,
-    marker: PhantomData<&'a mut List>,
+    next: Option<&'a mut Cons>,
+    tail: Option<&'a mut dyn ConsAny>,
 }
 
 impl<'a> Iterator for IterMut<'a> {
     type Item = &'a mut dyn ConsAny;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let cur = self.car.map(|car| unsafe { &mut *car.as_ptr() });
-        match self.cdr {
-            Some(cdr) => unsafe {
-                match (&*cdr.as_ptr()).as_ref_any().downcast_ref::<List>() {
-                    Some(next) => {
-                        self.car = next.head.car;
-                        self.cdr = next.head.cdr;
-                        return cur;
-                    }
-                    None => {}
-                }
-            },
-            None => {}
-        };
-        self.car = None;
-        self.cdr = None;
-        cur
+        let (car, cdr) = self.next.take()?.split_mut();
+        let car = car?;
+        if let Some(cdr) = cdr {
+            if cdr.cast_ref::<List>().is_some() {
+                self.next = cdr.cast_mut::<List>().unwrap().as_cons_mut();
+            } else {
+                self.tail = Some(cdr);
+            }
+        }
+        Some(car)
+    }
+}
+
+impl DottedIterator for IterMut<'_> {
+    fn take_tail(&mut self) -> Option<Self::Item> {
+        self.tail.take()
     }
 }
 
 #[derive(Debug)]
 pub struct IntoIter {
     next: List,
+    tail: Option<Box<dyn ConsAny>>,
 }
 
 impl Iterator for IntoIter {
     type Item = Box<dyn ConsAny>;
     fn next(&mut self) -> Option<Self::Item> {
-        let cur = self.next.head.car();
-        match self.next.cdr() {
-            Some(list) => {
-                self.next = list;
+        let head = self.next.head.as_mut()?;
+        let cur = head.car();
+        self.next = match head.cdr().map(into_list) {
+            Some(Ok(list)) => list,
+            Some(Err(tail)) => {
+                self.tail = Some(tail);
+                List::nil()
             }
-            _ => {}
-        }
+            None => List::nil(),
+        };
         cur
     }
 }
 
+impl DottedIterator for IntoIter {
+    fn take_tail(&mut self) -> Option<Self::Item> {
+        self.tail.take()
+    }
+}
+
 #[derive(Debug)]
 pub struct IntoIterDowncast<T> {
     next: List,
+    index: usize,
     marker: PhantomData<T>,
 }
 
 impl<T: 'static> Iterator for IntoIterDowncast<T> {
-    type Item = T;
+    type Item = Result<T, ListError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let cur = match self.next.head.car() {
-            Some(car) => match (car as Box<dyn Any>).downcast::<T>() {
-                Ok(car) => Some(*car),
-                Err(_) => None,
-            },
-            None => None,
-        };
-        // let cur = self.next.head.car();
-        match self.next.cdr() {
-            Some(list) => {
-                self.next = list;
-            }
-            _ => {}
+        let car = self.next.head.as_mut()?.car();
+        self.next = self.next.cdr().unwrap_or_default();
+        let car = car?;
+        self.index += 1;
+        if car.cast_ref::<T>().is_none() {
+            return Some(Err(wrong_element::<T>(self.index - 1, &*car)));
         }
-        cur
+        Some(Ok(*(car as Box<dyn Any>).downcast().unwrap()))
+    }
+}
+
+// a List known to hold only T's, see List::try_as
+pub struct TypedList<'a, T> {
+    list: &'a List,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: 'static> TypedList<'a, T> {
+    pub fn iter(&self) -> TypedIter<'a, T> {
+        TypedIter {
+            iter: self.list.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_null()
+    }
+
+    pub fn as_list(&self) -> &'a List {
+        self.list
+    }
+}
+
+impl<'a, T: 'static> IntoIterator for TypedList<'a, T> {
+    type IntoIter = TypedIter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct TypedIter<'a, T> {
+    iter: Iter<'a>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: 'static> Iterator for TypedIter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        // every element was checked by try_as
+        Some(self.iter.next()?.cast_ref().unwrap())
     }
 }
 
+// list!(1, 2, 3) is (1 2 3), list!(1, 2; 3) is the improper (1 2 . 3).
+// like a scheme quasiquote, @xs splices every element of xs, a List or
+// any other IntoIterator, and [...] is a nested list:
+//
+// list!(0, @xs, [1, [2]]; 3)    (0 x0 x1 ... (1 (2)) . 3)
+//
+// @xs moves xs, splice xs.clone() to keep it. an element that is an
+// array expression has to be wrapped in parens, list!(([1, 2]))
 #[macro_export]
 macro_rules! list {
-    ( $first:expr ) => (
-        List::new($first)
-    );
-
-    ( $first:expr, $( $a:expr ),* ) => {
-        {
-            let mut head = List::new($first);
-            let mut cur = &mut head;
-            $(
-                let next = List::new($a);
-                cur.set_cdr(Some(next));
-                cur = cur.cdr_mut().unwrap();
-            )*
-            head
-        }
+    () => {
+        $crate::utils::list::List::nil()
     };
+    (@push $items:ident; ) => {
+        $items.into_iter().collect::<$crate::utils::list::List>()
+    };
+    (@push $items:ident; ; [ $($tail:tt)* ]) => {
+        $crate::list!(@push $items; ; $crate::list!($($tail)*))
+    };
+    (@push $items:ident; ; $tail:expr) => {{
+        let mut list = $crate::list!(@push $items; );
+        list.set_dotted_tail($tail);
+        list
+    }};
+    (@push $items:ident; @ $xs:expr $(, $($rest:tt)*)?) => {{
+        $items.extend(IntoIterator::into_iter($xs).map($crate::utils::cons::into_box));
+        $crate::list!(@push $items; $($($rest)*)?)
+    }};
+    (@push $items:ident; @ $xs:expr ; $($tail:tt)+) => {{
+        $items.extend(IntoIterator::into_iter($xs).map($crate::utils::cons::into_box));
+        $crate::list!(@push $items; ; $($tail)+)
+    }};
+    (@push $items:ident; [ $($sub:tt)* ] $($rest:tt)*) => {
+        $crate::list!(@push $items; ($crate::list!($($sub)*)) $($rest)*)
+    };
+    (@push $items:ident; $item:expr $(, $($rest:tt)*)?) => {{
+        $items.push($crate::utils::cons::into_box($item));
+        $crate::list!(@push $items; $($($rest)*)?)
+    }};
+    (@push $items:ident; $item:expr ; $($tail:tt)+) => {{
+        $items.push($crate::utils::cons::into_box($item));
+        $crate::list!(@push $items; ; $($tail)+)
+    }};
+
+    ( $($items:tt)+ ) => {{
+        let mut items: Vec<Box<dyn $crate::utils::cons::ConsAny>> = Vec::new();
+        $crate::list!(@push items; $($items)+)
+    }};
 }
 
+#[cfg(test)]
 mod test {
     use super::*;
 
@@ -393,6 +711,162 @@ mod test {
         assert!(l.iter().eq(expect.iter()));
     }
 
+    #[test]
+    fn test_nil() {
+        let mut nil = list!();
+        assert!(nil.is_null());
+        assert_eq!(nil.len(), 0);
+        assert_eq!(nil, List::default());
+        assert_eq!(nil.iter().next(), None);
+        assert_eq!(nil.iter_mut().next(), None);
+        assert!(nil.car_ref::<i32>().is_none());
+        assert!(nil.cdr_ref().is_none());
+        assert!(nil.clone().into_iter().next().is_none());
+        assert!(nil.tail().is_null());
+        assert!(nil.clone().reverse().is_null());
+
+        let empty: List = Vec::<i32>::new().into_iter().collect();
+        assert!(empty.is_null());
+        nil.extend(vec![1, 2]);
+        assert_eq!(nil, list!(1, 2));
+
+        // (cons 1 '()) is (1)
+        let l = List::cons(1, List::nil());
+        assert_eq!(l.len(), 1);
+        assert!(l.cdr_ref().is_none());
+        assert_eq!(List::cons(0, l), list!(0, 1));
+
+        // '() as an element is still an element
+        let l = list!(list!(), 1);
+        assert_eq!(l.len(), 2);
+        assert!(l.car_ref::<List>().unwrap().is_null());
+    }
+
+    #[test]
+    fn test_splice() {
+        let xs = list!(2, 3);
+        assert_eq!(list!(1, @xs.clone(), 4), list!(1, 2, 3, 4));
+        assert_eq!(list!(@xs.clone()), xs);
+        assert_eq!(list!(@(0..3), @vec!["a"],), list!(0, 1, 2, "a"));
+        assert_eq!(list!(0, @List::nil()), list!(0));
+        assert_eq!(list!(1, @xs; 4).to_string(), "(1 2 3 . 4)");
+
+        let tree = list![[1, 2], [3, [4, []]], 5];
+        assert_eq!(tree.to_string(), "((1 2) (3 (4 ())) 5)");
+        assert_eq!(list!([]), list!(List::nil()));
+        assert_eq!(list!(1; [2, 3]), list!(1, 2, 3));
+        assert_eq!(list!([1; 2], -3; 4.0).to_string(), "((1 . 2) -3 . 4.0)");
+        // parens keep an array an element
+        assert_eq!(list!(([1, 2])).car_ref::<[i32; 2]>(), Some(&[1, 2]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_car_nil() {
+        List::nil().set_car(1);
+    }
+
+    #[test]
+    fn test_dotted() {
+        // (cons 1 2)
+        let mut pair = list!(1; 2);
+        assert_eq!(pair.len(), 1);
+        assert!(!pair.is_proper());
+        assert_eq!(pair.dotted_tail().unwrap(), &2);
+        assert!(pair.cdr_ref().is_none());
+        // cdr leaves a non List cdr in place
+        assert!(pair.cdr().is_none());
+        assert_eq!(pair.dotted_tail().unwrap(), &2);
+
+        let l = list!(1, 2, 3; 4);
+        assert_eq!(l.len(), 3);
+        assert_eq!(l.to_string(), "(1 2 3 . 4)");
+        assert_eq!(l, List::parse("(1 2 3 . 4)").unwrap());
+        assert_ne!(l, list!(1, 2, 3));
+        assert_ne!(l, list!(1, 2, 3; 5));
+        assert_eq!(l.clone(), l);
+        assert_eq!(l.clone().dotted_tail().unwrap(), &4);
+
+        // a List as tail is a proper cdr
+        let l = list!(1; list!(2, 3));
+        assert!(l.is_proper());
+        assert_eq!(l, list!(1, 2, 3));
+        assert_eq!(list!(1; list!()), list!(1));
+
+        let mut l = list!(1, 2; 3);
+        assert_eq!(*l.set_dotted_tail(4).unwrap(), 3);
+        assert_eq!(*l.take_dotted_tail().unwrap(), 4);
+        assert_eq!(l, list!(1, 2));
+        assert!(l.take_dotted_tail().is_none());
+    }
+
+    #[test]
+    fn test_dotted_iter() {
+        use super::{DottedIterator, Element};
+
+        let mut l = list!(1, 2; 3);
+        assert!(l.iter().eq([&1, &2].into_iter()));
+
+        let mut iter = l.iter().dotted();
+        assert_eq!(iter.next(), Some(Element::Car(&1 as &dyn ConsAny)));
+        assert_eq!(iter.next(), Some(Element::Car(&2 as &dyn ConsAny)));
+        assert_eq!(iter.next(), Some(Element::Tail(&3 as &dyn ConsAny)));
+        assert_eq!(iter.next(), None);
+
+        for item in l.iter_mut().dotted() {
+            if let Element::Tail(tail) = item {
+                *tail.cast_mut::<i32>().unwrap() = 4;
+            }
+        }
+        assert_eq!(l, list!(1, 2; 4));
+
+        let items: Vec<_> = l
+            .into_iter()
+            .dotted()
+            .map(|item| match item {
+                Element::Car(car) => *car.cast_ref::<i32>().unwrap(),
+                Element::Tail(tail) => -*tail.cast_ref::<i32>().unwrap(),
+            })
+            .collect();
+        assert_eq!(items, vec![1, 2, -4]);
+
+        let mut proper = list!(1).into_iter().dotted();
+        assert!(matches!(proper.next(), Some(Element::Car(_))));
+        assert!(proper.next().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_extend_improper() {
+        list!(1; 2).extend([3]);
+    }
+
+    #[test]
+    fn test_long_list() {
+        // none of these may recurse per element
+        let n = 10_000_000;
+        let mut l: List = (0..n).collect();
+        assert_eq!(l.len(), n as usize);
+        assert!(l.is_proper());
+        l.extend([n]);
+        l = l.reverse();
+        assert_eq!(l.car_ref::<i32>(), Some(&n));
+        drop(l);
+
+        let n = 1_000_000;
+        let l: List = (0..n).collect();
+        let mut l2 = l.clone();
+        assert_eq!(l, l2);
+        l2.tail().set_car(-1);
+        assert_ne!(l, l2);
+        assert_eq!(l.iter().count(), n as usize);
+
+        let mut dotted: List = (0..n).collect();
+        dotted.set_dotted_tail(n);
+        assert_eq!(dotted.clone(), dotted);
+        assert_ne!(dotted, l);
+    }
+
     #[test]
     fn test_clone() {
         let mut l = list!(1, 2, 3, list!(4));
@@ -410,4 +884,69 @@ mod test {
         l2 = l2.cdr().unwrap();
         assert_eq!(4, l2.car::<List>().unwrap().car::<i32>().unwrap());
     }
+
+    #[test]
+    fn test_try_accessors() {
+        let mut l = list!(1, "s"; 2.0);
+        assert_eq!(l.try_car_ref::<i32>(), Ok(&1));
+        let err = l.try_car_ref::<String>().unwrap_err();
+        assert_eq!(
+            err,
+            ListError::WrongType {
+                expected: "alloc::string::String",
+                actual: "i32"
+            }
+        );
+        assert_eq!(err.to_string(), "expected alloc::string::String, got i32");
+        // a failed try_car leaves the car in place
+        assert!(l.try_car::<f64>().is_err());
+        *l.try_car_mut::<i32>().unwrap() += 1;
+        assert_eq!(l.try_car::<i32>(), Ok(2));
+        let err = l.try_car::<i32>().unwrap_err();
+        assert_eq!(err, ListError::Taken);
+        assert_eq!(err.to_string(), "the car was taken out of the pair");
+        assert!(matches!(l.try_car_mut::<i32>(), Err(ListError::Taken)));
+        assert_eq!(List::nil().try_car::<i32>(), Err(ListError::Empty));
+
+        let mut rest = l.try_cdr().unwrap();
+        assert!(matches!(
+            rest.try_cdr_ref(),
+            Err(ListError::WrongType { actual: "f64", .. })
+        ));
+        assert!(rest.try_cdr().is_err());
+        assert_eq!(rest.dotted_tail().unwrap(), &2.0);
+        rest.take_dotted_tail();
+        assert_eq!(rest.try_cdr(), Ok(List::nil()));
+        assert_eq!(List::nil().try_cdr(), Err(ListError::Empty));
+    }
+
+    #[test]
+    fn test_try_as() {
+        let l = list!(1, 2, "three", 4);
+        // a mismatch no longer ends the iteration
+        let items: Vec<_> = l.iter_downcast::<i32>().collect();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[3], Ok(&4));
+        assert_eq!(
+            items[2].as_ref().unwrap_err().to_string(),
+            "element 2: expected i32, got &str"
+        );
+        let items: Vec<_> = l.clone().into_iter_downcast::<i32>().collect();
+        assert_eq!(items[3], Ok(4));
+        assert!(items[2].is_err());
+
+        assert!(matches!(
+            l.try_as::<i32>(),
+            Err(ListError::Element { index: 2, .. })
+        ));
+        let l = list!(1, 2, 3);
+        let ints = l.try_as::<i32>().unwrap();
+        assert_eq!(ints.len(), 3);
+        assert_eq!(ints.iter().sum::<i32>(), 6);
+        assert_eq!(
+            list!(1; 2).try_as::<i32>().err(),
+            Some(ListError::Improper { tail: "i32" })
+        );
+        assert!(List::nil().try_as::<String>().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,320 @@
+use std::{borrow::Cow, ops::Add};
+
+use super::{
+    cons::ConsAny,
+    equality::{eq, equal, eqv},
+    list::{List, ListError},
+};
+
+// srfi-1 style list procedures. they loop instead of recursing, so
+// they work on lists of any length. memq/memv/member are in equality.
+//
+// procedures that only look at a list take &self, those that return
+// a rearranged list take self and move the elements instead of
+// cloning them; these panic on an improper list
+//
+// take and drop are take_n and drop_n, List::take is mem::take
+
+fn assert_proper(list: &List, op: &str) {
+    assert!(list.is_proper(), "{}: improper list", op);
+}
+
+impl List {
+    // (list-ref list k)
+    pub fn list_ref(&self, k: usize) -> Option<&dyn ConsAny> {
+        self.iter().nth(k)
+    }
+
+    // (list-tail list k), the list after k cdrs. None when the list
+    // has fewer than k elements. the '() after the last pair isn't
+    // stored anywhere, so that one is a new List
+    pub fn list_tail(&self, k: usize) -> Option<Cow<'_, List>> {
+        let mut list = self;
+        for i in 0..k {
+            list = match list.as_cons()?.cdr_ref() {
+                None if i + 1 == k => return Some(Cow::Owned(List::nil())),
+                None => return None,
+                Some(cdr) => cdr.cast_ref()?,
+            };
+        }
+        Some(Cow::Borrowed(list))
+    }
+
+    // (last-pair list), None for '()
+    pub fn last_pair(&self) -> Option<&List> {
+        if self.is_null() {
+            return None;
+        }
+        let mut list = self;
+        while let Some(next) = list.cdr_ref() {
+            list = next;
+        }
+        Some(list)
+    }
+
+    // (append list other), other becomes the cdr of the last pair
+    pub fn append(self, other: List) -> List {
+        assert_proper(&self, "append");
+        if self.is_null() {
+            return other;
+        }
+        let mut list = self;
+        list.tail().set_cdr(Some(other));
+        list
+    }
+
+    // (assq key alist), the first entry whose car is eq? to key
+    pub fn assq(&self, key: &dyn ConsAny) -> Option<&List> {
+        self.assoc_by(key, eq)
+    }
+
+    pub fn assv(&self, key: &dyn ConsAny) -> Option<&List> {
+        self.assoc_by(key, eqv)
+    }
+
+    pub fn assoc(&self, key: &dyn ConsAny) -> Option<&List> {
+        self.assoc_by(key, equal)
+    }
+
+    fn assoc_by(
+        &self,
+        key: &dyn ConsAny,
+        same: fn(&dyn ConsAny, &dyn ConsAny) -> bool,
+    ) -> Option<&List> {
+        self.iter()
+            .filter_map(|entry| entry.cast_ref::<List>())
+            .find(|entry| {
+                entry
+                    .as_cons()
+                    .and_then(|pair| pair.car_ref())
+                    .is_some_and(|car| same(car, key))
+            })
+    }
+
+    // (delete x list), drops every element equal? to x
+    pub fn delete(self, x: &dyn ConsAny) -> List {
+        assert_proper(&self, "delete");
+        self.into_iter().filter(|item| !equal(&**item, x)).collect()
+    }
+
+    // (partition pred list) -> (in, out)
+    pub fn partition(self, mut pred: impl FnMut(&dyn ConsAny) -> bool) -> (List, List) {
+        assert_proper(&self, "partition");
+        let (mut yes, mut no) = (vec![], vec![]);
+        for item in self {
+            if pred(&*item) {
+                yes.push(item);
+            } else {
+                no.push(item);
+            }
+        }
+        (yes.into_iter().collect(), no.into_iter().collect())
+    }
+
+    // (split-at list k), cuts the list after k elements without
+    // copying. panics when the list has fewer than k elements
+    pub fn split_at(mut self, k: usize) -> (List, List) {
+        assert_proper(&self, "split_at");
+        assert!(k <= self.len(), "split_at: list too short");
+        if k == 0 {
+            return (List::nil(), self);
+        }
+        let mut last = &mut self;
+        for _ in 1..k {
+            last = last.cdr_mut().unwrap();
+        }
+        let rest = last.set_cdr(None).unwrap_or_default();
+        (self, rest)
+    }
+
+    // (take list k)
+    pub fn take_n(self, k: usize) -> List {
+        self.split_at(k).0
+    }
+
+    // (drop list k)
+    pub fn drop_n(self, k: usize) -> List {
+        self.split_at(k).1
+    }
+
+    // (zip list other) -> ((a0 b0) (a1 b1) ...), as long as the shorter one
+    pub fn zip(self, other: List) -> List {
+        assert_proper(&self, "zip");
+        assert_proper(&other, "zip");
+        self.into_iter()
+            .zip(other)
+            .map(|(a, b)| {
+                let mut pair = List::new(a);
+                pair.set_cdr(Some(List::new(b)));
+                pair
+            })
+            .collect()
+    }
+
+    // (unfold stop mapper successor seed)
+    pub fn unfold<S, T: ConsAny>(
+        mut stop: impl FnMut(&S) -> bool,
+        mut mapper: impl FnMut(&S) -> T,
+        mut successor: impl FnMut(S) -> S,
+        mut seed: S,
+    ) -> List {
+        let mut items = vec![];
+        while !stop(&seed) {
+            items.push(mapper(&seed));
+            seed = successor(seed);
+        }
+        items.into_iter().collect()
+    }
+
+    // (iota count start step)
+    pub fn iota<T>(count: usize, start: T, step: T) -> List
+    where
+        T: ConsAny + Add<Output = T> + Copy,
+    {
+        std::iter::successors(Some(start), |x| Some(*x + step))
+            .take(count)
+            .collect()
+    }
+
+    // (filter-map f list), keeps the Some results of f
+    pub fn filter_map<T: ConsAny>(&self, f: impl FnMut(&dyn ConsAny) -> Option<T>) -> List {
+        self.iter().filter_map(f).collect()
+    }
+
+    // (append-map f list), the lists returned by f joined together
+    pub fn flatmap(&self, mut f: impl FnMut(&dyn ConsAny) -> List) -> List {
+        let mut items = vec![];
+        for item in self.iter() {
+            items.extend(f(item));
+        }
+        items.into_iter().collect()
+    }
+
+    // (any pred list)
+    pub fn any(&self, pred: impl FnMut(&dyn ConsAny) -> bool) -> bool {
+        self.iter().any(pred)
+    }
+
+    // (every pred list), true for '()
+    pub fn every(&self, pred: impl FnMut(&dyn ConsAny) -> bool) -> bool {
+        self.iter().all(pred)
+    }
+
+    // (fold kons knil list), from the first element: f(f(init, a0), a1) ...
+    pub fn fold<A>(&self, init: A, f: impl FnMut(A, &dyn ConsAny) -> A) -> A {
+        self.iter().fold(init, f)
+    }
+
+    // (fold-right kons knil list), from the last element: f(a0, f(a1, init))
+    pub fn fold_right<A>(&self, init: A, mut f: impl FnMut(&dyn ConsAny, A) -> A) -> A {
+        let items: Vec<_> = self.iter().collect();
+        items.into_iter().rev().fold(init, |acc, item| f(item, acc))
+    }
+
+    // (reduce f ridentity list), every element has to be a T. like srfi-1
+    // f gets the element first: f(a2, f(a1, a0)) ...
+    pub fn reduce<T>(&self, mut f: impl FnMut(T, T) -> T, ridentity: T) -> Result<T, ListError>
+    where
+        T: ConsAny + Clone,
+    {
+        let mut items = self.try_as::<T>()?.iter().cloned();
+        Ok(match items.next() {
+            Some(first) => items.fold(first, |acc, item| f(item, acc)),
+            None => ridentity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, quote, sym, utils::symbol::Symbol};
+
+    #[test]
+    fn select() {
+        let l = list!(1, "two", list!(3), 4.0);
+        assert_eq!(l.list_ref(1).unwrap(), &"two");
+        assert!(l.list_ref(4).is_none());
+        assert_eq!(l.list_tail(2).as_deref(), Some(&list!(list!(3), 4.0)));
+        assert_eq!(l.list_tail(4).as_deref(), Some(&List::nil()));
+        assert!(l.list_tail(5).is_none());
+        assert_eq!(l.last_pair(), Some(&list!(4.0)));
+        assert!(List::nil().last_pair().is_none());
+        assert!(list!(1; 2).list_tail(1).is_none());
+
+        let alist = quote!(((a 1) (b 2) ("c" 3)));
+        assert_eq!(alist.assq(&sym!(b)), Some(&quote!((b 2))));
+        assert_eq!(alist.assoc(&"c"), Some(&quote!(("c" 3))));
+        assert!(alist.assq(&sym!(d)).is_none());
+        assert_eq!(list!(list!(2.5, 0)).assv(&2.5).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rearrange() {
+        let l = list!(1, 2, "x", 3);
+        assert_eq!(l.clone().append(list!(4)), list!(1, 2, "x", 3, 4));
+        assert_eq!(List::nil().append(list!(4)), list!(4));
+        assert_eq!(l.clone().delete(&"x"), list!(1, 2, 3));
+
+        let (ints, rest) = l.clone().partition(|x| x.cast_ref::<i32>().is_some());
+        assert_eq!((ints, rest), (list!(1, 2, 3), list!("x")));
+
+        assert_eq!(l.clone().split_at(1), (list!(1), list!(2, "x", 3)));
+        assert_eq!(l.clone().split_at(4), (l.clone(), List::nil()));
+        assert_eq!(l.clone().take_n(0), List::nil());
+        assert_eq!(l.clone().drop_n(2), list!("x", 3));
+
+        let zipped = list!(1, 2, 3).zip(quote!((a b)));
+        assert_eq!(zipped.to_string(), "((1 a) (2 b))");
+    }
+
+    #[test]
+    #[should_panic(expected = "split_at: list too short")]
+    fn split_short() {
+        list!(1, 2).split_at(3);
+    }
+
+    #[test]
+    fn build_and_fold() {
+        assert_eq!(List::iota(4, 0, 1), list!(0, 1, 2, 3));
+        assert_eq!(List::iota(3, 1.0, 0.5), list!(1.0, 1.5, 2.0));
+        let squares = List::unfold(|x| *x > 4, |x| x * x, |x| x + 1, 1);
+        assert_eq!(squares, list!(1, 4, 9, 16));
+
+        let l = list!(1, "a", 2, sym!(b), 3);
+        let doubled = l.filter_map(|x| x.cast_ref::<i32>().map(|n| n * 2));
+        assert_eq!(doubled, list!(2, 4, 6));
+        let pairs = list!(1, 2).flatmap(|x| list!(x.dyn_clone(), "-"));
+        assert_eq!(pairs, list!(1, "-", 2, "-"));
+        assert!(l.any(|x| x.cast_ref::<Symbol>().is_some()));
+        assert!(!l.every(|x| x.cast_ref::<i32>().is_some()));
+        assert!(List::nil().every(|_| false));
+
+        let ints = list!(1, 2, 3);
+        let minus = |acc: i32, x: &dyn ConsAny| acc - x.cast_ref::<i32>().unwrap();
+        assert_eq!(ints.fold(0, minus), -6);
+        // (fold-right - 0 '(1 2 3)) is (- 1 (- 2 (- 3 0)))
+        let minus = |x: &dyn ConsAny, acc: i32| x.cast_ref::<i32>().unwrap() - acc;
+        assert_eq!(ints.fold_right(0, minus), 2);
+        assert_eq!(ints.reduce(|a, b| a.max(b), 0), Ok(3));
+        // (reduce - 0 '(1 2 3)) is (- 3 (- 2 1))
+        assert_eq!(ints.reduce(|x: i32, acc| x - acc, 0), Ok(2));
+        assert_eq!(list!(5).reduce(|x: i32, acc| x - acc, 0), Ok(5));
+        assert_eq!(List::nil().reduce(|a: i32, b| a + b, 0), Ok(0));
+        assert!(l.reduce(|a: i32, b| a + b, 0).is_err());
+    }
+
+    #[test]
+    fn long_lists() {
+        let n = 1_000_000;
+        let l = List::iota(n, 0usize, 1);
+        assert_eq!(l.fold_right(0, |_, acc| acc + 1), n);
+        assert_eq!(l.list_tail(n - 1).as_deref(), Some(&list!(n - 1)));
+        let (even, odd) = l.partition(|x| x.cast_ref::<usize>().unwrap() % 2 == 0);
+        let all = even.append(odd);
+        assert_eq!(all.len(), n);
+        let (front, back) = all.split_at(n / 2);
+        assert_eq!(back.car_ref::<usize>(), Some(&1));
+        assert_eq!(front.zip(back).len(), n / 2);
+    }
+}
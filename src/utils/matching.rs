@@ -0,0 +1,225 @@
+use super::{cons::ConsAny, list::List};
+
+// match_list! destructures a List by shape and element type, trying
+// the arms in order like match. A pattern is a parenthesized list of
+//
+//   x: T      an element of type T, x is a &T
+//   x         any element, x is a &dyn ConsAny
+//   _         any element, not bound
+//   (...)     an element that is a List matching the inner pattern
+//   ..rest    the rest of the list, rest is a &List, last only
+//   ..        the rest of the list, not bound
+//   . tail    after an element, the cdr as is: the rest of the list or
+//             a dotted tail, tail is a &dyn ConsAny, last only
+//
+// without ..rest or . tail the list must end there. `_ => ...` matches
+// anything, and no matching arm panics.
+//
+// match_list!(m,
+//     (x: i32, (y: i32, ..rest)) => x + y + rest.len() as i32,
+//     (head . tail) => 0,
+//     _ => -1,
+// )
+//
+// types are the tokens up to the next `,` or `.`, so a type with a
+// comma in it (HashMap<K, V>) needs an alias
+#[macro_export]
+macro_rules! match_list {
+    ($list:expr, $($arms:tt)+) => {{
+        let list: &$crate::utils::list::List = &$list;
+        $crate::match_list!(@arms list; $($arms)+)
+    }};
+
+    (@arms $list:ident; _ => $body:expr $(, $($rest:tt)*)?) => {
+        $body
+    };
+    (@arms $list:ident; ( $($pat:tt)* ) => $body:expr $(, $($rest:tt)*)?) => {
+        match $crate::match_list!(@elems ($body); $list [$($pat)*]) {
+            Some(value) => value,
+            None => $crate::match_list!(@arms $list; $($($rest)*)?),
+        }
+    };
+    (@arms $list:ident; ) => {
+        panic!("match_list: no pattern matches {}", $list)
+    };
+
+    // $cur is the list left to match against [elements], the rest are
+    // the enclosing lists to go back to once it matched
+    (@elems $body:tt; $cur:ident [] $($stack:tt)*) => {
+        if $cur.is_null() {
+            $crate::match_list!(@next $body; $($stack)*)
+        } else {
+            None
+        }
+    };
+    (@elems $body:tt; $cur:ident [..] $($stack:tt)*) => {
+        $crate::match_list!(@next $body; $($stack)*)
+    };
+    (@elems $body:tt; $cur:ident [.. $rest:ident] $($stack:tt)*) => {{
+        let $rest: &$crate::utils::list::List = $cur;
+        $crate::match_list!(@next $body; $($stack)*)
+    }};
+    (@elems $body:tt; $cur:ident [( $($sub:tt)* ) $($elems:tt)*] $($stack:tt)*) => {
+        match $crate::utils::matching::car($cur)
+            .and_then(|car| car.cast_ref::<$crate::utils::list::List>())
+        {
+            Some(sub) => $crate::match_list!(@elems $body; sub [$($sub)*] $cur [$($elems)*] $($stack)*),
+            None => None,
+        }
+    };
+    (@elems $body:tt; $cur:ident [_ $($elems:tt)*] $($stack:tt)*) => {
+        match $crate::utils::matching::car($cur) {
+            Some(_) => $crate::match_list!(@advance $body; $cur [$($elems)*] $($stack)*),
+            None => None,
+        }
+    };
+    (@elems $body:tt; $cur:ident [$name:ident : $($elems:tt)*] $($stack:tt)*) => {
+        $crate::match_list!(@typed $body; $cur $name [] [$($elems)*] $($stack)*)
+    };
+    (@elems $body:tt; $cur:ident [$name:ident $($elems:tt)*] $($stack:tt)*) => {
+        match $crate::utils::matching::car($cur) {
+            Some($name) => $crate::match_list!(@advance $body; $cur [$($elems)*] $($stack)*),
+            None => None,
+        }
+    };
+
+    // collect the tokens of the type up to `,`, `.` or the end
+    (@typed $body:tt; $cur:ident $name:ident [$($ty:tt)+] [] $($stack:tt)*) => {
+        $crate::match_list!(@bind $body; $cur $name [$($ty)+] [] $($stack)*)
+    };
+    (@typed $body:tt; $cur:ident $name:ident [$($ty:tt)+] [, $($elems:tt)*] $($stack:tt)*) => {
+        $crate::match_list!(@bind $body; $cur $name [$($ty)+] [, $($elems)*] $($stack)*)
+    };
+    (@typed $body:tt; $cur:ident $name:ident [$($ty:tt)+] [. $($elems:tt)*] $($stack:tt)*) => {
+        $crate::match_list!(@bind $body; $cur $name [$($ty)+] [. $($elems)*] $($stack)*)
+    };
+    (@typed $body:tt; $cur:ident $name:ident [$($ty:tt)*] [$t:tt $($elems:tt)*] $($stack:tt)*) => {
+        $crate::match_list!(@typed $body; $cur $name [$($ty)* $t] [$($elems)*] $($stack)*)
+    };
+    (@bind $body:tt; $cur:ident $name:ident [$($ty:tt)+] [$($elems:tt)*] $($stack:tt)*) => {
+        match $crate::utils::matching::car($cur).and_then(|car| car.cast_ref::<$($ty)+>()) {
+            Some($name) => $crate::match_list!(@advance $body; $cur [$($elems)*] $($stack)*),
+            None => None,
+        }
+    };
+
+    // past the car of $cur, on to what follows the element
+    (@advance $body:tt; $cur:ident [] $($stack:tt)*) => {
+        if $crate::utils::matching::is_last($cur) {
+            $crate::match_list!(@next $body; $($stack)*)
+        } else {
+            None
+        }
+    };
+    (@advance $body:tt; $cur:ident [, $($elems:tt)*] $($stack:tt)*) => {{
+        let nil = $crate::utils::list::List::nil();
+        match $crate::utils::matching::cdr_list($cur, &nil) {
+            Some(rest) => $crate::match_list!(@elems $body; rest [$($elems)*] $($stack)*),
+            None => None,
+        }
+    }};
+    (@advance $body:tt; $cur:ident [. $tail:ident] $($stack:tt)*) => {{
+        let nil = $crate::utils::list::List::nil();
+        let $tail: &dyn $crate::utils::cons::ConsAny = $crate::utils::matching::cdr($cur, &nil);
+        $crate::match_list!(@next $body; $($stack)*)
+    }};
+
+    (@next ($body:expr); ) => {
+        Some($body)
+    };
+    (@next $body:tt; $cur:ident [$($elems:tt)*] $($stack:tt)*) => {
+        $crate::match_list!(@advance $body; $cur [$($elems)*] $($stack)*)
+    };
+}
+
+// helpers for the code match_list! expands to, list is always a pair
+pub fn car(list: &List) -> Option<&dyn ConsAny> {
+    list.as_cons()?.car_ref()
+}
+
+// the rest of the list, nil at the end, None for a dotted tail. the
+// '() after the last pair isn't stored, so the caller lends one
+pub fn cdr_list<'a>(list: &'a List, nil: &'a List) -> Option<&'a List> {
+    match list.as_cons()?.cdr_ref() {
+        None => Some(nil),
+        Some(cdr) => cdr.cast_ref(),
+    }
+}
+
+// the cdr as is, the rest of the list or a dotted tail, nil at the end
+pub fn cdr<'a>(list: &'a List, nil: &'a List) -> &'a dyn ConsAny {
+    match list.as_cons().and_then(|pair| pair.cdr_ref()) {
+        Some(cdr) => cdr,
+        None => nil,
+    }
+}
+
+// a cdr holding an explicit '() ends the list as well
+pub fn is_last(list: &List) -> bool {
+    list.as_cons()
+        .is_some_and(|pair| pair.cdr_ref().is_none_or(|cdr| cdr == &List::nil()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{list, quote, sym, utils::list::List, utils::symbol::Symbol};
+
+    fn describe(l: &List) -> String {
+        match_list!(l,
+            () => "nil".to_string(),
+            (x: i32) => format!("one int {}", x),
+            (x: i32, (y: i32, ..rest)) => format!("{} {} and {} more", x, y, rest.len()),
+            (op: Symbol, a, b) => format!("{}({:?}, {:?})", op, a, b),
+            (s: &str, _, ..rest) => format!("{} then {}", s, rest),
+            (head . tail) => format!("{:?} . {:?}", head, tail),
+        )
+    }
+
+    #[test]
+    fn match_shapes() {
+        assert_eq!(describe(&List::nil()), "nil");
+        assert_eq!(describe(&list!(1)), "one int 1");
+        assert_eq!(describe(&list!(1, list!(2, 3, 4))), "1 2 and 2 more");
+        assert_eq!(describe(&list!(1, list!(2))), "1 2 and 0 more");
+        assert_eq!(describe(&quote!((+ 1 (2)))), "+(1, (2))");
+        assert_eq!(describe(&list!("s", 1, 2)), "s then (2)");
+        assert_eq!(describe(&list!("s", 1)), "s then ()");
+        // falls through to the last arm
+        assert_eq!(describe(&list!(1, list!("x"))), "1 . ((\"x\"))");
+        assert_eq!(describe(&list!(1.5; 2)), "1.5 . 2");
+        assert_eq!(describe(&quote!((+ 1))), "Symbol(\"+\") . (1)");
+    }
+
+    #[test]
+    fn nested_dotted() {
+        let l = list!(list!(1; 2), list!(3));
+        let sum = match_list!(l,
+            ((a: i32 . b), (c: i32)) => a + b.cast_ref::<i32>().unwrap() + c,
+            _ => 0,
+        );
+        assert_eq!(sum, 6);
+
+        let tag = match_list!(quote!((point 1 2)),
+            (tag: Symbol, ..) => *tag,
+            _ => sym!(none),
+        );
+        assert_eq!(tag, sym!(point));
+    }
+
+    #[test]
+    fn explicit_nil_cdr() {
+        // (2 . ()) built by hand, the same list as (2)
+        let mut l = list!(1, 2);
+        let last = l.cdr_mut().unwrap().as_cons_mut().unwrap();
+        last.set_cdr(Some(List::nil()));
+        assert_eq!(describe(l.cdr_ref().unwrap()), "one int 2");
+        let sum = match_list!(l, (a: i32, b: i32) => a + b, _ => 0);
+        assert_eq!(sum, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "match_list: no pattern matches (1 2)")]
+    fn no_match() {
+        match_list!(list!(1, 2), (x: i32) => x);
+    }
+}
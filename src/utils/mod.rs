@@ -1,6 +1,23 @@
 pub mod base;
 pub mod cons;
+pub mod cxr;
+pub mod diagram;
+pub mod equality;
+pub mod hlist;
 // #[macro_use]
 pub mod list;
+pub mod list_lib;
+pub mod matching;
 pub mod ops;
+pub mod pair_graph;
+pub mod printer;
+pub mod procedure;
+pub mod rc_list;
+pub mod reader;
+pub mod registry;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod std_list;
+pub mod symbol;
+pub mod tree;
+pub mod value;
@@ -0,0 +1,436 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt::{self, Display, Write},
+};
+
+use super::{
+    cons::{into_box, ConsAny},
+    list::{DottedIterator, List},
+    printer::write_atom,
+};
+
+// mutable pairs that can be shared and linked into cycles, for the
+// set-car!/set-cdr! code of SICP 3.3. a List owns its cdr, so it can
+// never point back into itself; here every pair lives in a PairGraph
+// and cars and cdrs refer to pairs by PairId, which is Copy.
+// pairs are never freed, they live as long as their graph
+#[derive(Debug, Default, Clone)]
+pub struct PairGraph {
+    pairs: Vec<(Datum, Datum)>,
+}
+
+// a pair of the graph it was made by, using it with another graph
+// panics or points to an unrelated pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PairId(usize);
+
+// a car or cdr: '(), a pair, or any other value
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Datum {
+    #[default]
+    Nil,
+    Pair(PairId),
+    Atom(Box<dyn ConsAny>),
+}
+
+impl Datum {
+    pub fn atom<T: ConsAny>(atom: T) -> Self {
+        Datum::Atom(into_box(atom))
+    }
+
+    pub fn as_pair(&self) -> Option<PairId> {
+        match self {
+            Datum::Pair(pair) => Some(*pair),
+            _ => None,
+        }
+    }
+}
+
+impl From<PairId> for Datum {
+    fn from(pair: PairId) -> Self {
+        Datum::Pair(pair)
+    }
+}
+
+// where the cdrs of a list loop back: after start pairs the same len
+// pairs repeat forever
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl PairGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // number of pairs ever made
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    // (cons car cdr)
+    pub fn cons(&mut self, car: Datum, cdr: Datum) -> PairId {
+        self.pairs.push((car, cdr));
+        PairId(self.pairs.len() - 1)
+    }
+
+    // (list items...), every item an atom
+    pub fn list<T: ConsAny>(&mut self, items: impl IntoIterator<Item = T>) -> Datum {
+        let items: Vec<_> = items.into_iter().collect();
+        items.into_iter().rev().fold(Datum::Nil, |cdr, car| {
+            self.cons(Datum::atom(car), cdr).into()
+        })
+    }
+
+    // copies a List into new pairs, nested Lists and dotted tails too
+    pub fn add_list(&mut self, list: &List) -> Datum {
+        let mut iter = list.iter();
+        let items: Vec<_> = iter.by_ref().map(|item| self.add_item(item)).collect();
+        let tail = match iter.take_tail() {
+            Some(tail) => self.add_item(tail),
+            None => Datum::Nil,
+        };
+        items
+            .into_iter()
+            .rev()
+            .fold(tail, |cdr, car| self.cons(car, cdr).into())
+    }
+
+    fn add_item(&mut self, item: &dyn ConsAny) -> Datum {
+        match item.cast_ref::<List>() {
+            Some(list) => self.add_list(list),
+            None => Datum::Atom(item.dyn_clone()),
+        }
+    }
+
+    // copies the structure back into a List, a shared pair becomes one
+    // copy per reference. panics on a cycle
+    pub fn to_list(&self, datum: &Datum) -> List {
+        let mut path = HashSet::new();
+        let item: Box<dyn Any> = self.to_item(datum, &mut path);
+        match item.downcast::<List>() {
+            Ok(list) => *list,
+            Err(_) => panic!("to_list: not a list"),
+        }
+    }
+
+    // path holds the pairs being copied, meeting one again is a cycle
+    fn to_item(&self, datum: &Datum, path: &mut HashSet<PairId>) -> Box<dyn ConsAny> {
+        let Datum::Pair(head) = datum else {
+            return match datum {
+                Datum::Atom(atom) => atom.clone(),
+                _ => Box::new(List::nil()),
+            };
+        };
+        let mut spine = vec![];
+        let mut items = vec![];
+        let mut cur = Datum::Pair(*head);
+        while let Datum::Pair(pair) = cur {
+            assert!(path.insert(pair), "to_list: circular structure");
+            spine.push(pair);
+            items.push(self.to_item(self.car(pair), path));
+            cur = self.cdr(pair).clone();
+        }
+        let mut list: List = items.into_iter().collect();
+        if let Datum::Atom(tail) = cur {
+            list.set_dotted_tail(tail);
+        }
+        for pair in spine {
+            path.remove(&pair);
+        }
+        Box::new(list)
+    }
+
+    pub fn car(&self, pair: PairId) -> &Datum {
+        &self.pairs[pair.0].0
+    }
+
+    pub fn cdr(&self, pair: PairId) -> &Datum {
+        &self.pairs[pair.0].1
+    }
+
+    // (set-car! pair car), returns the old car
+    pub fn set_car(&mut self, pair: PairId, car: Datum) -> Datum {
+        std::mem::replace(&mut self.pairs[pair.0].0, car)
+    }
+
+    // (set-cdr! pair cdr), returns the old cdr
+    pub fn set_cdr(&mut self, pair: PairId, cdr: Datum) -> Datum {
+        std::mem::replace(&mut self.pairs[pair.0].1, cdr)
+    }
+
+    // the pair after pair along the cdrs
+    fn next(&self, pair: PairId) -> Option<PairId> {
+        self.cdr(pair).as_pair()
+    }
+
+    // (last-pair list), panics on a circular list
+    pub fn last_pair(&self, list: PairId) -> PairId {
+        assert!(!self.is_circular(&list.into()), "last_pair: circular list");
+        let mut pair = list;
+        while let Some(next) = self.next(pair) {
+            pair = next;
+        }
+        pair
+    }
+
+    // (append! x y), y becomes the cdr of the last pair of x
+    pub fn append_mut(&mut self, x: PairId, y: Datum) -> PairId {
+        let last = self.last_pair(x);
+        self.set_cdr(last, y);
+        x
+    }
+
+    // (make-cycle x), the last pair of x points back to x
+    pub fn make_cycle(&mut self, x: PairId) -> PairId {
+        self.append_mut(x, x.into())
+    }
+
+    // distinct pairs reachable through cars and cdrs, however they
+    // are shared or linked (ex 3.17)
+    pub fn count_pairs(&self, datum: &Datum) -> usize {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<PairId> = datum.as_pair().into_iter().collect();
+        while let Some(pair) = stack.pop() {
+            if seen.insert(pair) {
+                stack.extend(self.car(pair).as_pair());
+                stack.extend(self.next(pair));
+            }
+        }
+        seen.len()
+    }
+
+    // whether following the cdrs loops forever, Floyd's tortoise and
+    // hare in constant space (ex 3.18, 3.19)
+    pub fn is_circular(&self, datum: &Datum) -> bool {
+        let (mut slow, mut fast) = (datum.as_pair(), datum.as_pair());
+        loop {
+            fast = match fast.and_then(|pair| self.next(pair)) {
+                Some(pair) => self.next(pair),
+                None => return false,
+            };
+            slow = slow.and_then(|pair| self.next(pair));
+            if fast.is_none() {
+                return false;
+            }
+            if slow == fast {
+                return true;
+            }
+        }
+    }
+
+    // where the cdrs loop, Brent's algorithm, also in constant space.
+    // None for a list that ends
+    pub fn find_cycle(&self, datum: &Datum) -> Option<Cycle> {
+        let head = datum.as_pair()?;
+        // the length: the hare moves on, the tortoise waits at every
+        // power of two until the hare comes around to it
+        let (mut power, mut len) = (1, 1);
+        let mut tortoise = head;
+        let mut hare = self.next(head)?;
+        while tortoise != hare {
+            if power == len {
+                tortoise = hare;
+                power *= 2;
+                len = 0;
+            }
+            hare = self.next(hare)?;
+            len += 1;
+        }
+        // the start: with the hare len pairs ahead, they meet where
+        // the cycle starts
+        let (mut tortoise, mut hare) = (head, head);
+        for _ in 0..len {
+            hare = self.next(hare)?;
+        }
+        let mut start = 0;
+        while tortoise != hare {
+            tortoise = self.next(tortoise)?;
+            hare = self.next(hare)?;
+            start += 1;
+        }
+        Some(Cycle { start, len })
+    }
+
+    // prints datum in scheme notation. a pair that is reached more
+    // than once gets a label, #0=(...), and is written #0# after that,
+    // so shared and circular structure prints in finite space
+    pub fn display<'a>(&'a self, datum: &'a Datum) -> Labeled<'a> {
+        Labeled { graph: self, datum }
+    }
+
+    // the pairs reachable from datum more than once
+    fn shared(&self, datum: &Datum) -> HashSet<PairId> {
+        let mut seen = HashSet::new();
+        let mut shared = HashSet::new();
+        let mut stack: Vec<PairId> = datum.as_pair().into_iter().collect();
+        while let Some(pair) = stack.pop() {
+            if !seen.insert(pair) {
+                shared.insert(pair);
+                continue;
+            }
+            stack.extend(self.car(pair).as_pair());
+            stack.extend(self.next(pair));
+        }
+        shared
+    }
+}
+
+pub struct Labeled<'a> {
+    graph: &'a PairGraph,
+    datum: &'a Datum,
+}
+
+struct Labels {
+    shared: HashSet<PairId>,
+    assigned: HashMap<PairId, usize>,
+}
+
+impl Labeled<'_> {
+    fn write(&self, f: &mut impl Write, datum: &Datum, labels: &mut Labels) -> fmt::Result {
+        let pair = match datum {
+            Datum::Nil => return f.write_str("()"),
+            Datum::Atom(atom) => return write_atom(f, &**atom),
+            Datum::Pair(pair) => *pair,
+        };
+        if let Some(label) = labels.assigned.get(&pair) {
+            return write!(f, "#{}#", label);
+        }
+        if labels.shared.contains(&pair) {
+            let label = labels.assigned.len();
+            labels.assigned.insert(pair, label);
+            write!(f, "#{}=", label)?;
+        }
+        f.write_char('(')?;
+        self.write(f, self.graph.car(pair), labels)?;
+        let mut cdr = self.graph.cdr(pair);
+        loop {
+            match cdr {
+                Datum::Nil => break,
+                // a labeled pair can't be written inside the list
+                Datum::Pair(pair) if !labels.shared.contains(pair) => {
+                    f.write_char(' ')?;
+                    self.write(f, self.graph.car(*pair), labels)?;
+                    cdr = self.graph.cdr(*pair);
+                }
+                _ => {
+                    f.write_str(" . ")?;
+                    self.write(f, cdr, labels)?;
+                    break;
+                }
+            }
+        }
+        f.write_char(')')
+    }
+}
+
+impl Display for Labeled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut labels = Labels {
+            shared: self.graph.shared(self.datum),
+            assigned: HashMap::new(),
+        };
+        self.write(f, self.datum, &mut labels)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, quote, sym};
+
+    #[test]
+    fn mutate() {
+        let mut g = PairGraph::new();
+        let x = g.add_list(&quote!((a (b c) d)));
+        assert_eq!(g.len(), 5);
+        assert_eq!(g.display(&x).to_string(), "(a (b c) d)");
+        assert_eq!(g.to_list(&x), quote!((a (b c) d)));
+
+        let x = x.as_pair().unwrap();
+        g.set_car(x, Datum::atom(1));
+        let y = g.list([2, 3]);
+        g.append_mut(x, y);
+        assert_eq!(
+            g.to_list(&x.into()),
+            list!(1, [sym!(b), sym!(c)], sym!(d), 2, 3)
+        );
+        let last = g.last_pair(x);
+        g.set_cdr(last, Datum::atom(4.5));
+        assert_eq!(g.display(&x.into()).to_string(), "(1 (b c) d 2 3 . 4.5)");
+        assert_eq!(g.to_list(&Datum::Nil), List::nil());
+    }
+
+    #[test]
+    fn share_and_cycle() {
+        let mut g = PairGraph::new();
+        // (define x (list 'a 'b)) (list x x)
+        let x = g.add_list(&quote!((a b)));
+        let l = g.list([0]).as_pair().unwrap();
+        g.set_car(l, x.clone());
+        let l = g.cons(x.clone(), l.into()).into();
+        assert_eq!(g.count_pairs(&l), 4);
+        assert_eq!(g.display(&l).to_string(), "(#0=(a b) #0#)");
+        assert_eq!(g.to_list(&l), quote!(((a b) (a b))));
+
+        let c = g.list(["a", "b", "c"]).as_pair().unwrap();
+        g.make_cycle(c);
+        let c = c.into();
+        assert_eq!(g.count_pairs(&c), 3);
+        assert_eq!(g.display(&c).to_string(), "#0=(\"a\" \"b\" \"c\" . #0#)");
+        assert!(g.is_circular(&c) && !g.is_circular(&l));
+
+        // (1 2 . #0=(3 4 5 . #0#))
+        let tail = g.list([3, 4, 5]).as_pair().unwrap();
+        g.make_cycle(tail);
+        let rho = g.cons(Datum::atom(2), tail.into());
+        let rho = g.cons(Datum::atom(1), rho.into()).into();
+        assert_eq!(g.display(&rho).to_string(), "(1 2 . #0=(3 4 5 . #0#))");
+        assert_eq!(g.find_cycle(&rho), Some(Cycle { start: 2, len: 3 }));
+        assert_eq!(g.find_cycle(&c), Some(Cycle { start: 0, len: 3 }));
+        assert_eq!(g.find_cycle(&l), None);
+
+        // a pair whose car is itself
+        let p = g.cons(Datum::Nil, Datum::Nil);
+        g.set_car(p, p.into());
+        assert_eq!(g.display(&p.into()).to_string(), "#0=(#0#)");
+        assert!(!g.is_circular(&p.into()));
+        assert_eq!(g.count_pairs(&p.into()), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "to_list: circular structure")]
+    fn cycle_to_list() {
+        let mut g = PairGraph::new();
+        let p = g.cons(Datum::Nil, Datum::Nil);
+        g.set_car(p, p.into());
+        g.to_list(&p.into());
+    }
+
+    #[test]
+    fn long_cycle() {
+        let mut g = PairGraph::new();
+        let head = g.list(0..1_000_000).as_pair().unwrap();
+        assert!(!g.is_circular(&head.into()));
+        let last = g.last_pair(head);
+        let middle = (0..500_000).fold(head, |pair, _| g.next(pair).unwrap());
+        g.set_cdr(last, middle.into());
+        assert!(g.is_circular(&head.into()));
+        let cycle = g.find_cycle(&head.into());
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 500_000,
+                len: 500_000
+            })
+        );
+        assert_eq!(g.count_pairs(&head.into()), 1_000_000);
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt::{self, Debug, Display, Write};
+
+use super::{cons::Cons, cons::ConsAny, list::List, symbol::Symbol};
+
+// write one element the way scheme `write` does, so that
+// List::parse can read back what is printed here.
+// unknown atom types fall back to their Debug output
+pub(crate) fn write_atom(f: &mut impl Write, atom: &dyn ConsAny) -> fmt::Result {
+    if let Some(cons) = as_pair(atom) {
+        return write_pair(f, cons);
+    }
+    let any = atom.as_ref_any();
+    if any.downcast_ref::<List>().is_some() {
+        return f.write_str("()");
+    }
+    if let Some(b) = any.downcast_ref::<bool>() {
+        return f.write_str(if *b { "#t" } else { "#f" });
+    }
+    if let Some(n) = any.downcast_ref::<f64>() {
+        return write_float(f, *n);
+    }
+    if let Some(n) = any.downcast_ref::<f32>() {
+        return write_float(f, *n);
+    }
+    if let Some(s) = any.downcast_ref::<String>() {
+        return write_string(f, s);
+    }
+    if let Some(s) = any.downcast_ref::<&'static str>() {
+        return write_string(f, s);
+    }
+    if let Some(s) = any.downcast_ref::<Symbol>() {
+        return write!(f, "{}", s);
+    }
+    macro_rules! display {
+        ($($t:ty),*) => {
+            $(
+                if let Some(n) = any.downcast_ref::<$t>() {
+                    return write!(f, "{}", n);
+                }
+            )*
+        };
+    }
+    display!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+    write!(f, "{:?}", atom)
+}
+
+// an f32 is printed as itself, widened to f64 0.1 would come out as
+// 0.10000000149011612
+fn write_float<F: Into<f64> + Copy + Debug>(f: &mut impl Write, n: F) -> fmt::Result {
+    let wide: f64 = n.into();
+    if wide.is_nan() {
+        f.write_str("+nan.0")
+    } else if wide.is_infinite() {
+        f.write_str(if wide > 0.0 { "+inf.0" } else { "-inf.0" })
+    } else {
+        // Debug keeps the `.0` of whole floats, Display drops it
+        write!(f, "{:?}", n)
+    }
+}
+
+fn write_string(f: &mut impl Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            '\0' => f.write_str("\\0")?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+// both a non empty List and a bare Cons are pairs, a List is only a
+// Cons whose cdr is expected to be another List
+pub(crate) fn as_pair(atom: &dyn ConsAny) -> Option<&Cons> {
+    let any = atom.as_ref_any();
+    if let Some(list) = any.downcast_ref::<List>() {
+        return list.as_cons();
+    }
+    any.downcast_ref::<Cons>()
+}
+
+pub(crate) fn is_nil(atom: &dyn ConsAny) -> bool {
+    atom.cast_ref::<List>().is_some_and(List::is_null)
+}
+
+// elements of the list starting at cons, plus the final cdr when it
+// is not a pair (dotted tail). A missing car is an empty slot and skipped
+fn elements(mut cons: &Cons) -> (Vec<&dyn ConsAny>, Option<&dyn ConsAny>) {
+    let mut items = vec![];
+    loop {
+        if let Some(car) = cons.car_ref() {
+            items.push(car);
+        }
+        match cons.cdr_ref() {
+            None => return (items, None),
+            Some(cdr) if is_nil(cdr) => return (items, None),
+            Some(cdr) => match as_pair(cdr) {
+                Some(next) => cons = next,
+                None => return (items, Some(cdr)),
+            },
+        }
+    }
+}
+
+fn write_pair(f: &mut impl Write, cons: &Cons) -> fmt::Result {
+    let (items, tail) = elements(cons);
+    f.write_char('(')?;
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            f.write_char(' ')?;
+        }
+        write_atom(f, *item)?;
+    }
+    if let Some(tail) = tail {
+        f.write_str(" . ")?;
+        write_atom(f, tail)?;
+    }
+    f.write_char(')')
+}
+
+// width-aware printing: a pair that fits in the remaining width is
+// printed on one line, otherwise every element after the first goes
+// on its own line aligned under the first one
+//
+// ((1 2 3)
+//  (4 5 6)
+//  (7 8 9))
+pub fn pretty(atom: &dyn ConsAny, width: usize) -> String {
+    let mut out = String::new();
+    write_pretty(&mut out, atom, 0, width);
+    out
+}
+
+fn write_pretty(out: &mut String, atom: &dyn ConsAny, indent: usize, width: usize) {
+    let mut flat = String::new();
+    write_atom(&mut flat, atom).unwrap();
+    let cons = match as_pair(atom) {
+        Some(cons) if indent + flat.chars().count() > width => cons,
+        _ => {
+            out.push_str(&flat);
+            return;
+        }
+    };
+
+    let (items, tail) = elements(cons);
+    let indent = indent + 1;
+    out.push('(');
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            newline(out, indent);
+        }
+        write_pretty(out, *item, indent, width);
+    }
+    if let Some(tail) = tail {
+        newline(out, indent);
+        out.push_str(". ");
+        write_pretty(out, tail, indent + 2, width);
+    }
+    out.push(')');
+}
+
+fn newline(out: &mut String, indent: usize) {
+    out.push('\n');
+    out.extend(std::iter::repeat_n(' ', indent));
+}
+
+impl List {
+    pub fn pretty(&self, width: usize) -> String {
+        pretty(self, width)
+    }
+}
+
+impl Cons {
+    pub fn pretty(&self, width: usize) -> String {
+        pretty(self, width)
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_atom(f, self)
+    }
+}
+
+impl Debug for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_atom(f, self)
+    }
+}
+
+impl Display for Cons {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_pair(f, self)
+    }
+}
+
+impl Debug for Cons {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_pair(f, self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        list,
+        utils::{cons::Cons, list::List, symbol::Symbol},
+    };
+
+    #[test]
+    fn display_list() {
+        let l = list!(1, list!(2.0, list!(true)), "a\"b", Symbol::new("x"), 'c');
+        assert_eq!(l.to_string(), "(1 (2.0 (#t)) \"a\\\"b\" x 'c')");
+        assert_eq!(format!("{:?}", list!(1, 2)), "(1 2)");
+        assert_eq!(list!(list!(), 1).to_string(), "(() 1)");
+    }
+
+    #[test]
+    fn display_floats() {
+        let l = list!(0.1f32, 2.0f32, 0.1, f32::NAN, f32::NEG_INFINITY);
+        assert_eq!(l.to_string(), "(0.1 2.0 0.1 +nan.0 -inf.0)");
+    }
+
+    #[test]
+    fn display_cons() {
+        let pair = Cons::new(Some(Box::new(1)), Some(Box::new(2)));
+        assert_eq!(pair.to_string(), "(1 . 2)");
+
+        // cons onto a list continues the list notation
+        let pair = Cons::new(Some(Box::new(0)), Some(Box::new(list!(1, 2))));
+        assert_eq!(pair.to_string(), "(0 1 2)");
+
+        let inner = Cons::new(Some(Box::new(2)), Some(Box::new(3)));
+        let pair = Cons::new(Some(Box::new(1)), Some(Box::new(inner)));
+        assert_eq!(pair.to_string(), "(1 2 . 3)");
+
+        let pair = Cons::new(Some(Box::new(list!(1, 2))), Some(Box::new(3)));
+        assert_eq!(format!("{:?}", pair), "((1 2) . 3)");
+        assert_eq!(Cons::new(Some(Box::new(1)), None).to_string(), "(1)");
+    }
+
+    #[test]
+    fn pretty_print() {
+        let m = List::parse("((1 2 3) (4 5 6) (7 8 9))").unwrap();
+        assert_eq!(m.pretty(80), "((1 2 3) (4 5 6) (7 8 9))");
+        assert_eq!(m.pretty(20), "((1 2 3)\n (4 5 6)\n (7 8 9))");
+
+        // mobile of ex2_29: (left-branch right-branch), branch: (length structure)
+        let mobile = List::parse("((10 10) (10 ((3 5) (9 3))))").unwrap();
+        assert_eq!(mobile.pretty(14), "((10 10)\n (10\n  ((3 5)\n   (9 3))))");
+
+        let pair = Cons::new(Some(Box::new(list!(1, 2, 3))), Some(Box::new(list!(4, 5))));
+        assert_eq!(pair.pretty(10), "((1 2 3)\n 4\n 5)");
+        let pair = Cons::new(Some(Box::new(list!(1, 2, 3))), Some(Box::new(4)));
+        assert_eq!(pair.pretty(10), "((1 2 3)\n . 4)");
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    fmt::{self, Debug},
+    ops::Deref,
+    rc::Rc,
+};
+
+// a procedure as a list element, e.g. (list square inc).
+// several arguments are passed as a tuple: Procedure<(i32, i32), i32>.
+// clones share the same closure, and two procedures are equal only
+// when they are clones of each other, like eq? on lambdas
+pub struct Procedure<A, R = A> {
+    name: Option<&'static str>,
+    f: Rc<dyn Fn(A) -> R>,
+}
+
+impl<A, R> Procedure<A, R> {
+    pub fn new(f: impl Fn(A) -> R + 'static) -> Self {
+        Self {
+            name: None,
+            f: Rc::new(f),
+        }
+    }
+
+    // the name is only used by Debug
+    pub fn named(name: &'static str, f: impl Fn(A) -> R + 'static) -> Self {
+        Self {
+            name: Some(name),
+            f: Rc::new(f),
+        }
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    pub fn call(&self, arg: A) -> R {
+        (self.f)(arg)
+    }
+}
+
+// the boxed closures returned by compose, repeat, smooth ...
+impl<A, R> From<Box<dyn Fn(A) -> R>> for Procedure<A, R> {
+    fn from(f: Box<dyn Fn(A) -> R>) -> Self {
+        Self {
+            name: None,
+            f: Rc::from(f),
+        }
+    }
+}
+
+// lets a procedure be called as proc(x)
+impl<A, R> Deref for Procedure<A, R> {
+    type Target = dyn Fn(A) -> R;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.f
+    }
+}
+
+impl<A, R> Clone for Procedure<A, R> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<A, R> PartialEq for Procedure<A, R> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.f, &other.f)
+    }
+}
+
+// #<procedure square> or #<procedure i32 -> i32> when unnamed
+impl<A, R> Debug for Procedure<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "#<procedure {}>", name),
+            None => write!(
+                f,
+                "#<procedure {} -> {}>",
+                std::any::type_name::<A>(),
+                std::any::type_name::<R>()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, utils::list::List};
+
+    #[test]
+    fn procedure_list() {
+        let square = Procedure::named("square", |x: i32| x * x);
+        let inc = Procedure::new(|x: i32| x + 1);
+        let mut ops = list!(square.clone(), inc.clone(), 10);
+        assert_eq!(
+            ops.to_string(),
+            "(#<procedure square> #<procedure i32 -> i32> 10)"
+        );
+
+        let applied: Vec<i32> = ops
+            .iter()
+            .filter_map(|op| op.cast_ref::<Procedure<i32>>())
+            .map(|op| op(3))
+            .collect();
+        assert_eq!(applied, [9, 4]);
+
+        // equal only to itself and its clones
+        assert_eq!(ops.car_ref::<Procedure<i32>>(), Some(&square));
+        assert_ne!(square, Procedure::named("square", |x: i32| x * x));
+        let copy = ops.clone();
+        assert_eq!(copy, ops);
+        assert_eq!(ops.car::<Procedure<i32>>().unwrap().call(5), 25);
+        assert!(copy.car_ref::<Procedure<i32>>().unwrap().name() == Some("square"));
+    }
+
+    #[test]
+    fn dispatch_table() {
+        let add = Procedure::named("+", |(a, b): (i32, i32)| a + b);
+        let sub = Procedure::named("-", |(a, b): (i32, i32)| a - b);
+        let table = list!(list!("add", add), list!("sub", sub));
+        let lookup = |key: &str| {
+            table
+                .iter()
+                .filter_map(|entry| entry.cast_ref::<List>())
+                .find(|entry| entry.car_ref::<&str>() == Some(&key))
+                .and_then(|entry| entry.cdr_ref()?.car_ref::<Procedure<(i32, i32), i32>>())
+        };
+        assert_eq!(lookup("add").unwrap()((1, 2)), 3);
+        assert_eq!(lookup("sub").unwrap()((1, 2)), -1);
+        assert!(lookup("mul").is_none());
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    any::Any,
+    fmt::{self, Debug, Display, Write},
+    rc::Rc,
+};
+
+use super::{
+    cons::{into_box, ConsAny},
+    list::List,
+    printer::write_atom,
+};
+
+// a persistent list: pairs are immutable and reference counted, so
+// cons, cdr, append and map share the tails and elements they reuse
+// instead of deep cloning them like List does. Only proper lists
+#[derive(Clone)]
+pub struct RcList {
+    head: Option<Rc<Node>>,
+}
+
+struct Node {
+    car: Rc<dyn ConsAny>,
+    cdr: RcList,
+}
+
+impl RcList {
+    pub fn new<T: ConsAny>(car: T) -> Self {
+        Self::cons(car, Self::nil())
+    }
+
+    // the empty list '()
+    pub const fn nil() -> Self {
+        Self { head: None }
+    }
+
+    // (cons car cdr), O(1), cdr is shared with the new list
+    pub fn cons<T: ConsAny>(car: T, cdr: RcList) -> Self {
+        Self::cons_rc(Rc::from(into_box(car)), cdr)
+    }
+
+    fn cons_rc(car: Rc<dyn ConsAny>, cdr: RcList) -> Self {
+        Self {
+            head: Some(Rc::new(Node { car, cdr })),
+        }
+    }
+
+    // builds (items... . tail) from the back, tail is shared
+    fn from_rcs(items: Vec<Rc<dyn ConsAny>>, tail: RcList) -> Self {
+        items
+            .into_iter()
+            .rev()
+            .fold(tail, |cdr, car| Self::cons_rc(car, cdr))
+    }
+
+    // (null? list)
+    pub fn is_null(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_null()
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn car(&self) -> Option<&dyn ConsAny> {
+        self.head.as_ref().map(|node| &*node.car)
+    }
+
+    pub fn car_ref<T: 'static>(&self) -> Option<&T> {
+        self.car()?.cast_ref()
+    }
+
+    // O(1), the returned list is the tail of self, not a copy
+    pub fn cdr(&self) -> Option<Self> {
+        self.head.as_ref().map(|node| node.cdr.clone())
+    }
+
+    // (append self other), copies the pairs of self and shares other
+    pub fn append(&self, other: &RcList) -> Self {
+        let items = self.nodes().map(|node| node.car.clone()).collect();
+        Self::from_rcs(items, other.clone())
+    }
+
+    // (map f self), the values returned by f are stored as is, so f
+    // returning a cons onto an RcList element shares that element
+    pub fn map<T: ConsAny>(&self, mut f: impl FnMut(&dyn ConsAny) -> T) -> Self {
+        let items = self.iter().map(|x| Rc::from(into_box(f(x)))).collect();
+        Self::from_rcs(items, Self::nil())
+    }
+
+    pub fn reverse(&self) -> Self {
+        self.nodes().fold(Self::nil(), |cdr, node| {
+            Self::cons_rc(node.car.clone(), cdr)
+        })
+    }
+
+    // whether both lists start at the same pair
+    pub fn ptr_eq(&self, other: &RcList) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &Node> {
+        std::iter::successors(self.head.as_deref(), |node| node.cdr.head.as_deref())
+    }
+}
+
+impl Default for RcList {
+    fn default() -> Self {
+        Self::nil()
+    }
+}
+
+// a shared tail is dropped by its last owner, the loop stops at the
+// first pair still referenced elsewhere
+impl Drop for RcList {
+    fn drop(&mut self) {
+        let mut next = self.head.take();
+        while let Some(node) = next {
+            next = match Rc::try_unwrap(node) {
+                Ok(mut node) => node.cdr.head.take(),
+                Err(_) => break,
+            };
+        }
+    }
+}
+
+impl PartialEq for RcList {
+    fn eq(&self, other: &RcList) -> bool {
+        let (mut a, mut b) = (self.head.as_ref(), other.head.as_ref());
+        loop {
+            match (a, b) {
+                (None, None) => return true,
+                // the rest is the same shared tail
+                (Some(x), Some(y)) if Rc::ptr_eq(x, y) => return true,
+                (Some(x), Some(y)) => {
+                    if !(*x.car).dyn_eq(&*y.car) {
+                        return false;
+                    }
+                    (a, b) = (x.cdr.head.as_ref(), y.cdr.head.as_ref());
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl<T: ConsAny> FromIterator<T> for RcList {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items = iter.into_iter().map(|x| Rc::from(into_box(x))).collect();
+        Self::from_rcs(items, Self::nil())
+    }
+}
+
+impl<'a> IntoIterator for &'a RcList {
+    type IntoIter = Iter<'a>;
+    type Item = &'a dyn ConsAny;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a> {
+    next: Option<&'a Node>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a dyn ConsAny;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.cdr.head.as_deref();
+        Some(&*node.car)
+    }
+}
+
+// nested Lists become nested RcLists, panics on an improper list
+impl From<List> for RcList {
+    fn from(list: List) -> Self {
+        assert!(list.is_proper(), "RcList: improper list");
+        list.into_iter()
+            .map(|item| match item.cast_ref::<List>() {
+                Some(_) => {
+                    let inner = (item as Box<dyn Any>).downcast::<List>().unwrap();
+                    Box::new(RcList::from(*inner)) as Box<dyn ConsAny>
+                }
+                None => item,
+            })
+            .collect()
+    }
+}
+
+// nested RcLists become nested Lists, other elements are cloned
+impl From<&RcList> for List {
+    fn from(list: &RcList) -> Self {
+        list.iter()
+            .map(|item| match item.cast_ref::<RcList>() {
+                Some(inner) => Box::new(List::from(inner)) as Box<dyn ConsAny>,
+                None => item.dyn_clone(),
+            })
+            .collect()
+    }
+}
+
+impl Display for RcList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('(')?;
+        for (idx, item) in self.iter().enumerate() {
+            if idx > 0 {
+                f.write_char(' ')?;
+            }
+            write_atom(f, item)?;
+        }
+        f.write_char(')')
+    }
+}
+
+impl Debug for RcList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::list;
+
+    #[test]
+    fn share_tails() {
+        let tail: RcList = [2, 3].into_iter().collect();
+        let a = RcList::cons(1, tail.clone());
+        let b = RcList::cons("b", tail.clone());
+        assert_eq!(a.to_string(), "(1 2 3)");
+        assert_eq!(b.to_string(), "(\"b\" 2 3)");
+        assert!(a.cdr().unwrap().ptr_eq(&tail));
+        assert!(b.cdr().unwrap().ptr_eq(&tail));
+
+        // append copies the first list only
+        let c = a.append(&b);
+        assert_eq!(c.to_string(), "(1 2 3 \"b\" 2 3)");
+        let mut rest = c.clone();
+        for _ in 0..3 {
+            rest = rest.cdr().unwrap();
+        }
+        assert!(rest.ptr_eq(&b));
+        assert!(!c.ptr_eq(&a));
+
+        drop((a, b, tail));
+        assert_eq!(c.len(), 6);
+        assert_eq!(c.reverse().to_string(), "(3 2 \"b\" 3 2 1)");
+        assert!(RcList::nil().cdr().is_none());
+    }
+
+    #[test]
+    fn map_shares_elements() {
+        let inner: RcList = [1, 2].into_iter().collect();
+        let l = RcList::new(inner.clone());
+        let mapped = l.map(|x| RcList::cons(0, x.cast_ref::<RcList>().unwrap().clone()));
+        let first = mapped.car_ref::<RcList>().unwrap();
+        assert_eq!(first.to_string(), "(0 1 2)");
+        assert!(first.cdr().unwrap().ptr_eq(&inner));
+        assert_eq!(
+            l.map(|x| x.cast_ref::<RcList>().unwrap().len()),
+            RcList::new(2usize)
+        );
+    }
+
+    #[test]
+    fn convert_list() {
+        let l = list!(1, list!(2, list!(3)), "s", list!());
+        let rc = RcList::from(l.clone());
+        assert_eq!(rc.to_string(), "(1 (2 (3)) \"s\" ())");
+        assert!(rc.iter().nth(1).unwrap().cast_ref::<RcList>().is_some());
+        assert_eq!(List::from(&rc), l);
+        assert_eq!(List::from(&RcList::nil()), List::nil());
+    }
+
+    #[test]
+    fn long_list() {
+        let l: RcList = (0..1_000_000).collect();
+        let shared = RcList::cons(-1, l.clone());
+        assert_eq!(shared.len(), 1_000_001);
+        assert_eq!(l, l.clone());
+        assert_eq!(shared.cdr().unwrap(), l.reverse().reverse());
+        drop(l);
+        assert_eq!(shared.car_ref::<i32>(), Some(&-1));
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    iter::Peekable,
+    str::{Chars, FromStr},
+};
+
+use super::{cons::ConsAny, list::List, symbol::Symbol};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedEof,
+    UnclosedList,
+    UnexpectedCloseParen,
+    UnterminatedString,
+    InvalidEscape(char),
+    InvalidToken(String),
+    // `.` outside the second last position of a list
+    MisplacedDot,
+    ExpectedList,
+    TrailingInput,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            ParseErrorKind::UnclosedList => write!(f, "list is never closed"),
+            ParseErrorKind::UnexpectedCloseParen => write!(f, "unexpected `)`"),
+            ParseErrorKind::UnterminatedString => write!(f, "string is never terminated"),
+            ParseErrorKind::InvalidEscape(c) => write!(f, "invalid escape `\\{}`", c),
+            ParseErrorKind::InvalidToken(token) => write!(f, "invalid token `{}`", token),
+            ParseErrorKind::MisplacedDot => write!(f, "misplaced `.`"),
+            ParseErrorKind::ExpectedList => write!(f, "expected a list"),
+            ParseErrorKind::TrailingInput => write!(f, "unexpected input after datum"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// reads scheme data one by one from text:
+// integers -> i32 (i64 if out of range), floats -> f64, strings -> String,
+// #t/#f -> bool, lists -> List, anything else -> Symbol.
+// (a b . c) is an improper List with dotted tail c,
+// 'x is read as (quote x), `;` starts a line comment.
+pub struct Reader<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            chars: src.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    // next datum, None when only whitespace and comments are left
+    pub fn read(&mut self) -> Option<Result<Box<dyn ConsAny>, ParseError>> {
+        self.skip_atmosphere();
+        self.chars.peek()?;
+        Some(self.read_datum())
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        self.error_at((self.line, self.column), kind)
+    }
+
+    fn error_at(&self, (line, column): (usize, usize), kind: ParseErrorKind) -> ParseError {
+        ParseError { line, column, kind }
+    }
+
+    // whitespace and comments
+    fn skip_atmosphere(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == ';' {
+                while !matches!(self.next_char(), Some('\n') | None) {}
+            } else if c.is_whitespace() {
+                self.next_char();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn read_datum(&mut self) -> Result<Box<dyn ConsAny>, ParseError> {
+        let pos = (self.line, self.column);
+        match self.read_item()? {
+            Item::Datum(datum) => Ok(datum),
+            Item::Dot => Err(self.error_at(pos, ParseErrorKind::MisplacedDot)),
+        }
+    }
+
+    fn read_item(&mut self) -> Result<Item, ParseError> {
+        let pos = (self.line, self.column);
+        let datum: Box<dyn ConsAny> = match self.chars.peek() {
+            None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+            Some('(') => {
+                self.next_char();
+                Box::new(self.read_list(pos)?)
+            }
+            Some(')') => return Err(self.error(ParseErrorKind::UnexpectedCloseParen)),
+            Some('"') => {
+                self.next_char();
+                Box::new(self.read_string(pos)?)
+            }
+            Some('\'') => {
+                self.next_char();
+                self.skip_atmosphere();
+                let quoted = self.read_datum()?;
+                let mut list = List::new(Symbol::new("quote"));
+                list.set_cdr(Some(List::new(quoted)));
+                Box::new(list)
+            }
+            Some(_) => {
+                let token = self.read_token();
+                if token == "." {
+                    return Ok(Item::Dot);
+                }
+                atom(&token).map_err(|kind| self.error_at(pos, kind))?
+            }
+        };
+        Ok(Item::Datum(datum))
+    }
+
+    // called after `(`, `open` is where the `(` is
+    fn read_list(&mut self, open: (usize, usize)) -> Result<List, ParseError> {
+        let mut items: Vec<Box<dyn ConsAny>> = vec![];
+        loop {
+            self.skip_atmosphere();
+            match self.chars.peek() {
+                None => return Err(self.error_at(open, ParseErrorKind::UnclosedList)),
+                Some(')') => {
+                    self.next_char();
+                    break;
+                }
+                Some(_) => {}
+            }
+            let pos = (self.line, self.column);
+            match self.read_item()? {
+                Item::Datum(datum) => items.push(datum),
+                Item::Dot if items.is_empty() => {
+                    return Err(self.error_at(pos, ParseErrorKind::MisplacedDot))
+                }
+                Item::Dot => return self.read_dotted_tail(open, items),
+            }
+        }
+        Ok(items.into_iter().collect())
+    }
+
+    // called after the `.` of (a b . c)
+    fn read_dotted_tail(
+        &mut self,
+        open: (usize, usize),
+        items: Vec<Box<dyn ConsAny>>,
+    ) -> Result<List, ParseError> {
+        self.skip_atmosphere();
+        let tail = self.read_datum()?;
+        self.skip_atmosphere();
+        match self.chars.peek() {
+            None => Err(self.error_at(open, ParseErrorKind::UnclosedList)),
+            Some(')') => {
+                self.next_char();
+                let mut list: List = items.into_iter().collect();
+                list.set_dotted_tail(tail);
+                Ok(list)
+            }
+            Some(_) => Err(self.error(ParseErrorKind::MisplacedDot)),
+        }
+    }
+
+    // called after `"`, `open` is where the `"` is
+    fn read_string(&mut self, open: (usize, usize)) -> Result<String, ParseError> {
+        let mut s = String::new();
+        loop {
+            match self.next_char() {
+                None => return Err(self.error_at(open, ParseErrorKind::UnterminatedString)),
+                Some('"') => return Ok(s),
+                Some('\\') => {
+                    let pos = (self.line, self.column - 1);
+                    let escaped = match self.next_char() {
+                        None => return Err(self.error_at(open, ParseErrorKind::UnterminatedString)),
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('0') => '\0',
+                        Some(c @ ('\\' | '"')) => c,
+                        Some(c) => return Err(self.error_at(pos, ParseErrorKind::InvalidEscape(c))),
+                    };
+                    s.push(escaped);
+                }
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> String {
+        let mut token = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';' | '\'') {
+                break;
+            }
+            token.push(c);
+            self.next_char();
+        }
+        token
+    }
+}
+
+enum Item {
+    Datum(Box<dyn ConsAny>),
+    Dot,
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<Box<dyn ConsAny>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read()
+    }
+}
+
+fn atom(token: &str) -> Result<Box<dyn ConsAny>, ParseErrorKind> {
+    match token {
+        "#t" | "#true" => return Ok(Box::new(true)),
+        "#f" | "#false" => return Ok(Box::new(false)),
+        "+inf.0" => return Ok(Box::new(f64::INFINITY)),
+        "-inf.0" => return Ok(Box::new(f64::NEG_INFINITY)),
+        "+nan.0" | "-nan.0" => return Ok(Box::new(f64::NAN)),
+        _ => {}
+    }
+    if token.starts_with('#') {
+        return Err(ParseErrorKind::InvalidToken(token.to_string()));
+    }
+    if let Ok(n) = token.parse::<i64>() {
+        return Ok(match i32::try_from(n) {
+            Ok(n) => Box::new(n),
+            Err(_) => Box::new(n),
+        });
+    }
+    if is_numeric(token) {
+        return token
+            .parse::<f64>()
+            .map(|n| Box::new(n) as Box<dyn ConsAny>)
+            .map_err(|_| ParseErrorKind::InvalidToken(token.to_string()));
+    }
+    Ok(Box::new(Symbol::new(token)))
+}
+
+// token starts like a number: [+-]digit or [+-].digit
+fn is_numeric(token: &str) -> bool {
+    let unsigned = token.strip_prefix(['+', '-']).unwrap_or(token);
+    let digits = unsigned.strip_prefix('.').unwrap_or(unsigned);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+impl List {
+    // read exactly one list from scheme text, e.g. "(1 (2 3) \"s\" foo 4.5 #t)"
+    pub fn parse(src: &str) -> Result<List, ParseError> {
+        let mut reader = Reader::new(src);
+        reader.skip_atmosphere();
+        let pos = (reader.line, reader.column);
+        let datum = match reader.read() {
+            None => return Err(reader.error(ParseErrorKind::UnexpectedEof)),
+            Some(datum) => datum?,
+        };
+        let list = match (datum as Box<dyn std::any::Any>).downcast::<List>() {
+            Ok(list) => *list,
+            Err(_) => return Err(reader.error_at(pos, ParseErrorKind::ExpectedList)),
+        };
+        reader.skip_atmosphere();
+        if reader.chars.peek().is_some() {
+            return Err(reader.error(ParseErrorKind::TrailingInput));
+        }
+        Ok(list)
+    }
+}
+
+impl FromStr for List {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        List::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::list;
+
+    #[test]
+    fn parse_atoms() {
+        let l = List::parse("(1 (2 3) \"s\" foo 4.5 #t)").unwrap();
+        let expect = list!(
+            1,
+            list!(2, 3),
+            "s".to_string(),
+            Symbol::new("foo"),
+            4.5,
+            true
+        );
+        assert_eq!(l, expect);
+
+        let l = List::parse("(-7 +3 3000000000 -.5 1e3 #f \"a\\\"b\\n\" 'x)").unwrap();
+        let expect = list!(
+            -7,
+            3,
+            3000000000i64,
+            -0.5,
+            1000.0,
+            false,
+            "a\"b\n".to_string(),
+            list!(Symbol::new("quote"), Symbol::new("x"))
+        );
+        assert_eq!(l, expect);
+    }
+
+    #[test]
+    fn parse_fixture() {
+        let src = "
+            ; matrix from ex2_37
+            ((1 2 3)
+             (4 5 6)) ; trailing comment
+        ";
+        let l: List = src.parse().unwrap();
+        assert_eq!(l, list!(list!(1, 2, 3), list!(4, 5, 6)));
+
+        assert_eq!(List::parse("(a . (b c))").unwrap().to_string(), "(a b c)");
+        assert_eq!(List::parse("(a . ())").unwrap().to_string(), "(a)");
+
+        let mut reader = Reader::new("1 foo (2) ; done");
+        assert_eq!(*reader.next().unwrap().unwrap(), 1);
+        assert_eq!(*reader.next().unwrap().unwrap(), Symbol::new("foo"));
+        assert_eq!(*reader.next().unwrap().unwrap(), List::new(2));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn parse_errors() {
+        fn err(src: &str) -> (usize, usize, ParseErrorKind) {
+            let e = List::parse(src).unwrap_err();
+            (e.line, e.column, e.kind)
+        }
+        assert_eq!(err("(1 2"), (1, 1, ParseErrorKind::UnclosedList));
+        assert_eq!(err("(1 2))"), (1, 6, ParseErrorKind::TrailingInput));
+        assert_eq!(err(")"), (1, 1, ParseErrorKind::UnexpectedCloseParen));
+        assert_eq!(
+            err("(1\n  \"ab"),
+            (2, 3, ParseErrorKind::UnterminatedString)
+        );
+        assert_eq!(
+            err("(\"a\\q\")"),
+            (1, 4, ParseErrorKind::InvalidEscape('q'))
+        );
+        assert_eq!(
+            err("(1\n 2 #x)"),
+            (2, 4, ParseErrorKind::InvalidToken("#x".to_string()))
+        );
+        assert_eq!(
+            err("(1.2.3)"),
+            (1, 2, ParseErrorKind::InvalidToken("1.2.3".to_string()))
+        );
+        assert_eq!(err("  foo"), (1, 3, ParseErrorKind::ExpectedList));
+        assert_eq!(err("(. 1)"), (1, 2, ParseErrorKind::MisplacedDot));
+        assert_eq!(err("(1 . 2 3)"), (1, 8, ParseErrorKind::MisplacedDot));
+        assert_eq!(err("(1 . 2 . 3)"), (1, 8, ParseErrorKind::MisplacedDot));
+        assert_eq!(err("."), (1, 1, ParseErrorKind::MisplacedDot));
+        assert_eq!(err(""), (1, 1, ParseErrorKind::UnexpectedEof));
+        assert_eq!(
+            List::parse("(1\n  (2 3 x").unwrap_err().to_string(),
+            "2:3: list is never closed"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for src in [
+            "(1 (2 3) \"s\" foo 4.5 #t)",
+            "(((1)) -2 3000000000 1.0 0.1 1e100 #f)",
+            "(\"a \\\"quoted\\\" \\\\ \\n\" sym-bol +inf.0 -inf.0)",
+            "(quote (a b))",
+            "(() (()) 1)",
+            "(1 (2 . 3) . \"s\")",
+            "()",
+        ] {
+            let l = List::parse(src).unwrap();
+            assert_eq!(l.to_string(), src);
+            assert_eq!(List::parse(&l.to_string()).unwrap(), l);
+        }
+    }
+}
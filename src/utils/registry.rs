@@ -0,0 +1,383 @@
+use std::{
+    any::{Any, TypeId},
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{OnceLock, RwLock},
+};
+
+use num::rational::Rational64;
+
+use super::{
+    cons::ConsAny,
+    list::{DottedIterator, List},
+    symbol::Symbol,
+};
+
+// a total order and a matching hash for dyn ConsAny and List, so that
+// a List can be a HashMap key or a BTreeSet element through Key.
+//
+// List itself is only PartialEq: == on floats says NaN != NaN, which
+// no Eq/Ord can agree with. the order here is an equivalence of its
+// own, Key(a) == Key(b) exactly when a.total_cmp(b) is Equal, and
+// total_hash agrees with it. it differs from == in that NaN equals
+// NaN, and in how atoms of unregistered types compare.
+//
+// the blanket ConsAny impl can't require Hash or Ord of every atom
+// type, so the atom types that have them are registered here. atoms
+// order by the rank of their type, then by value; the built in types
+// rank as
+//
+//   bool < char < integers < floats < rationals < strings < symbols < lists
+//
+// with each integer and float type a rank of its own: 2 < 1.0 and
+// 3i64 < 1u8. types registered later rank after these in the order
+// they were registered. atoms of types that were never registered
+// order after everything else, by type name and then by Debug output,
+// and are equal when both of those are.
+//
+// register a type before any of its values are compared or hashed:
+// registering changes how its values order and hash, so Keys already
+// in a map or set would be left out of place.
+//
+// this is the one registry of atom types, serialize::register adds
+// the serde tag of a type to the same entry
+type HashFn = fn(&dyn ConsAny, &mut dyn Hasher);
+type CmpFn = fn(&dyn ConsAny, &dyn ConsAny) -> Ordering;
+
+#[derive(Clone, Copy)]
+struct Order {
+    rank: usize,
+    hash: HashFn,
+    cmp: CmpFn,
+}
+
+// what is known about one atom type
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Entry {
+    order: Option<Order>,
+    #[cfg(feature = "serde")]
+    pub(crate) serde: Option<super::serialize::Serde>,
+}
+
+#[derive(Default)]
+pub(crate) struct Types {
+    pub(crate) by_id: HashMap<TypeId, Entry>,
+    // the number of ordered types, the rank of the next one
+    ranks: usize,
+    #[cfg(feature = "serde")]
+    pub(crate) by_tag: HashMap<&'static str, TypeId>,
+}
+
+impl Types {
+    // a type that is ordered already keeps its rank
+    fn order(&mut self, id: TypeId, hash: HashFn, cmp: CmpFn) {
+        let entry = self.by_id.entry(id).or_default();
+        if entry.order.is_none() {
+            let rank = self.ranks;
+            entry.order = Some(Order { rank, hash, cmp });
+            self.ranks += 1;
+        }
+    }
+}
+
+fn hash_as<T: Hash + 'static>(x: &dyn ConsAny, mut state: &mut dyn Hasher) {
+    x.cast_ref::<T>().unwrap().hash(&mut state)
+}
+
+fn cmp_as<T: Ord + 'static>(a: &dyn ConsAny, b: &dyn ConsAny) -> Ordering {
+    a.cast_ref::<T>().unwrap().cmp(b.cast_ref::<T>().unwrap())
+}
+
+// 0.0 and -0.0 are equal, so they have to hash the same. NaN is after
+// every number and equal to any NaN, whatever its payload
+fn hash_float<T: Into<f64> + Copy + 'static>(x: &dyn ConsAny, state: &mut dyn Hasher) {
+    let x: f64 = (*x.cast_ref::<T>().unwrap()).into();
+    let x = match x {
+        _ if x == 0.0 => 0.0,
+        _ if x.is_nan() => f64::NAN,
+        _ => x,
+    };
+    state.write_u64(x.to_bits())
+}
+
+fn cmp_float<T: Into<f64> + Copy + 'static>(a: &dyn ConsAny, b: &dyn ConsAny) -> Ordering {
+    let a: f64 = (*a.cast_ref::<T>().unwrap()).into();
+    let b: f64 = (*b.cast_ref::<T>().unwrap()).into();
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+pub(crate) fn types() -> &'static RwLock<Types> {
+    static TYPES: OnceLock<RwLock<Types>> = OnceLock::new();
+    TYPES.get_or_init(|| {
+        let mut types = Types::default();
+        macro_rules! ord {
+            ($($t:ty),*) => {
+                $( types.order(TypeId::of::<$t>(), hash_as::<$t>, cmp_as::<$t>); )*
+            };
+        }
+        ord!(bool, char);
+        ord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+        types.order(TypeId::of::<f32>(), hash_float::<f32>, cmp_float::<f32>);
+        types.order(TypeId::of::<f64>(), hash_float::<f64>, cmp_float::<f64>);
+        ord!(Rational64, String, &'static str, Symbol);
+        types.order(
+            TypeId::of::<List>(),
+            |x, state| x.cast_ref::<List>().unwrap().total_hash(state),
+            |a, b| {
+                let (a, b) = (a.cast_ref::<List>(), b.cast_ref::<List>());
+                a.unwrap().total_cmp(b.unwrap())
+            },
+        );
+        RwLock::new(types)
+    })
+}
+
+// makes T hashable and ordered as an atom, ranking after every type
+// registered before it. registering a type again keeps its rank
+pub fn register<T: ConsAny + Hash + Ord>() {
+    let mut types = types().write().unwrap();
+    types.order(TypeId::of::<T>(), hash_as::<T>, cmp_as::<T>);
+}
+
+pub(crate) fn entry(x: &dyn ConsAny) -> Option<Entry> {
+    let id = Any::type_id(x.as_ref_any());
+    types().read().unwrap().by_id.get(&id).copied()
+}
+
+fn order(x: &dyn ConsAny) -> Option<Order> {
+    entry(x)?.order
+}
+
+impl dyn ConsAny {
+    // whether the type of this atom was registered
+    pub fn is_ordered(&self) -> bool {
+        order(self).is_some()
+    }
+
+    pub fn total_hash(&self, mut state: &mut dyn Hasher) {
+        match order(self) {
+            Some(order) => {
+                Any::type_id(self.as_ref_any()).hash(&mut state);
+                (order.hash)(self, state);
+            }
+            None => {
+                self.type_name().hash(&mut state);
+                format!("{:?}", self).hash(&mut state);
+            }
+        }
+    }
+
+    pub fn total_cmp(&self, other: &dyn ConsAny) -> Ordering {
+        match (order(self), order(other)) {
+            (Some(a), Some(b)) if a.rank == b.rank => (a.cmp)(self, other),
+            (Some(a), Some(b)) => a.rank.cmp(&b.rank),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self
+                .type_name()
+                .cmp(other.type_name())
+                .then_with(|| format!("{:?}", self).cmp(&format!("{:?}", other))),
+        }
+    }
+}
+
+impl List {
+    pub fn total_hash(&self, state: &mut dyn Hasher) {
+        let mut iter = self.iter();
+        let mut len = 0;
+        for item in iter.by_ref() {
+            item.total_hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
+        if let Some(tail) = iter.take_tail() {
+            tail.total_hash(state);
+        }
+    }
+
+    // element by element, a list that ends first is less: () < (1) < (1 2),
+    // and (1 . 2) < (1 2). with the same elements, a proper list is less
+    // than a dotted one
+    pub fn total_cmp(&self, other: &List) -> Ordering {
+        let (mut a, mut b) = (self.iter(), other.iter());
+        loop {
+            match (a.next(), b.next()) {
+                (Some(x), Some(y)) => match x.total_cmp(y) {
+                    Ordering::Equal => {}
+                    order => return order,
+                },
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (None, None) => {
+                    return match (a.take_tail(), b.take_tail()) {
+                        (Some(x), Some(y)) => x.total_cmp(y),
+                        (x, y) => x.is_some().cmp(&y.is_some()),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub trait TotalOrd {
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    fn total_hash(&self, state: &mut dyn Hasher);
+}
+
+impl TotalOrd for List {
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        List::total_cmp(self, other)
+    }
+
+    fn total_hash(&self, state: &mut dyn Hasher) {
+        List::total_hash(self, state)
+    }
+}
+
+impl TotalOrd for Box<dyn ConsAny> {
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        (**self).total_cmp(&**other)
+    }
+
+    fn total_hash(&self, state: &mut dyn Hasher) {
+        (**self).total_hash(state)
+    }
+}
+
+// Key(&list) borrows instead
+impl<T: TotalOrd> TotalOrd for &T {
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        T::total_cmp(self, other)
+    }
+
+    fn total_hash(&self, state: &mut dyn Hasher) {
+        T::total_hash(self, state)
+    }
+}
+
+// Eq, Ord and Hash by the total order, for map keys and set elements
+#[derive(Debug, Clone)]
+pub struct Key<T>(pub T);
+
+impl<T: TotalOrd> PartialEq for Key<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_cmp(&other.0) == Ordering::Equal
+    }
+}
+
+impl<T: TotalOrd> Eq for Key<T> {}
+
+impl<T: TotalOrd> Hash for Key<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.total_hash(state)
+    }
+}
+
+impl<T: TotalOrd> Ord for Key<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<T: TotalOrd> PartialOrd for Key<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{hash_map::DefaultHasher, BTreeSet, HashMap, HashSet};
+
+    use super::*;
+    use crate::{list, quote, sym};
+
+    fn hash(list: &List) -> u64 {
+        let mut state = DefaultHasher::new();
+        Key(list).hash(&mut state);
+        state.finish()
+    }
+
+    #[test]
+    fn mixed_order() {
+        let mut items = list!(
+            sym!(b),
+            "s".to_string(),
+            2.5,
+            [2],
+            1,
+            true,
+            sym!(a),
+            [1, 2],
+            [],
+            -3,
+            'c',
+            [1; 2]
+        )
+        .into_iter()
+        .collect::<Vec<_>>();
+        items.sort_by(|a, b| a.total_cmp(b));
+        let sorted: List = items.into_iter().collect();
+        assert_eq!(
+            sorted.to_string(),
+            "(#t 'c' -3 1 2.5 \"s\" a b () (1 . 2) (1 2) (2))"
+        );
+        assert!(Key(list!(1, 2)) < Key(list!(1, 3)));
+        assert!(Key(list!(2i64)) < Key(list!(1u8)));
+        assert_eq!(list!(0.0).total_cmp(&list!(-0.0)), Ordering::Equal);
+        assert!(Key(list!(f64::NAN)) > Key(list!(f64::INFINITY)));
+    }
+
+    #[test]
+    fn nan() {
+        // not ==, but the same key, and hashed the same whatever the payload
+        let (a, b) = (list!(f64::NAN), list!(-f64::NAN));
+        assert_ne!(a, a);
+        assert_eq!(Key(&a), Key(&b));
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(hash(&list!(0.0)), hash(&list!(-0.0)));
+        let set: BTreeSet<_> = [Key(a), Key(b), Key(list!(1.0))].into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn hash_keys() {
+        let mut memo = HashMap::new();
+        memo.insert(Key(quote!((fib 10))), 55);
+        memo.insert(Key(list!(sym!(fib), 10; 2)), 0);
+        assert_eq!(memo.get(&Key(list!(sym!(fib), 10))), Some(&55));
+        assert_eq!(memo.get(&Key(quote!((fib 11)))), None);
+        assert_eq!(memo.len(), 2);
+
+        let set: HashSet<_> = [list!(0.0), list!(-0.0), list!([1], "x")].map(Key).into();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Key(list!([1], "x"))));
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    struct Point(i32, i32);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Opaque(f64);
+
+    #[test]
+    fn registered_types() {
+        let points = list!(Point(1, 2));
+        let other = list!(Point(0, 5));
+        register::<Point>();
+        register::<Point>();
+        assert!(Key(&other) < Key(&points));
+        assert!(Key(&points) < Key(&list!(Opaque(0.0))));
+        assert!(Key(list!(Opaque(1.0))) > Key(list!(Opaque(0.5))));
+        assert!(!(&Opaque(1.0) as &dyn ConsAny).is_ordered());
+        let set: BTreeSet<_> = [points.clone(), other, points].map(Key).into();
+        assert_eq!(set.len(), 2);
+
+        // unregistered atoms are the same key when their Debug output is
+        let opaque = list!(Opaque(1.0));
+        assert_eq!(Key(&opaque), Key(&list!(Opaque(1.0))));
+        assert_eq!(hash(&opaque), hash(&list!(Opaque(1.0))));
+    }
+}
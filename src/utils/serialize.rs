@@ -0,0 +1,483 @@
+use std::{
+    any::{Any, TypeId},
+    fmt,
+};
+
+use num::rational::Rational64;
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{Error as _, SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use super::{
+    cons::{Cons, ConsAny},
+    list::List,
+    registry::{entry, types},
+    symbol::Symbol,
+};
+
+// serde support for List and Cons, with the serde feature. any self
+// describing format works, the examples are JSON.
+//
+// a proper list is a sequence of its elements and '() is []. bool, i32,
+// f64 and String atoms are plain values; a &str is written as a
+// string too and reads back as a String. a dotted list and every other
+// atom is a map with a single key, its tag:
+//
+// (1 "a" (b 2.5) . 3)   [1, "a", [{"symbol": "b"}, 2.5]] dotted:
+//                       {"dotted": [1, "a", [{"symbol": "b"}, 2.5], 3]}
+//
+// built in tags are dotted (the tail is the last element), cons
+// ([car, cdr], null for a missing one), symbol, char, rational
+// ([numer, denom]) and the other integer and float types by name, e.g.
+// {"i64": 7}. other atom types are written through register, as
+// {tag: value}; an atom of a type that was not registered fails to
+// serialize
+type SerializeFn = fn(&dyn ConsAny) -> &dyn erased_serde::Serialize;
+type DeserializeFn =
+    for<'de> fn(&mut dyn erased_serde::Deserializer<'de>) -> Result<Box<dyn ConsAny>, String>;
+
+// the serde half of a registry::Entry
+#[derive(Clone, Copy)]
+pub(crate) struct Serde {
+    tag: &'static str,
+    serialize: SerializeFn,
+    deserialize: DeserializeFn,
+}
+
+const BUILT_IN_TAGS: &[&str] = &[
+    "dotted", "cons", "symbol", "char", "rational", "i8", "i16", "i64", "i128", "isize", "u8",
+    "u16", "u32", "u64", "u128", "usize", "f32",
+];
+
+fn serialize_as<T: Serialize + 'static>(atom: &dyn ConsAny) -> &dyn erased_serde::Serialize {
+    atom.cast_ref::<T>().unwrap()
+}
+
+fn deserialize_as<T: ConsAny + DeserializeOwned>(
+    deserializer: &mut dyn erased_serde::Deserializer<'_>,
+) -> Result<Box<dyn ConsAny>, String> {
+    match erased_serde::deserialize::<T>(deserializer) {
+        Ok(atom) => Ok(Box::new(atom)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// lets atoms of type T be serialized as {tag: value}, in the same
+// registry as registry::register. panics when the tag is taken by a
+// built in or another type
+pub fn register<T: ConsAny + Serialize + DeserializeOwned>(tag: &'static str) {
+    assert!(
+        !BUILT_IN_TAGS.contains(&tag),
+        "register: {} is built in",
+        tag
+    );
+    let mut types = types().write().unwrap();
+    let id = TypeId::of::<T>();
+    let old = types.by_id.get(&id).and_then(|entry| entry.serde);
+    if old.is_some_and(|old| old.tag == tag) {
+        return;
+    }
+    assert!(
+        !types.by_tag.contains_key(tag),
+        "register: {} is taken",
+        tag
+    );
+    if let Some(old) = old {
+        types.by_tag.remove(old.tag);
+    }
+    types.by_tag.insert(tag, id);
+    types.by_id.entry(id).or_default().serde = Some(Serde {
+        tag,
+        serialize: serialize_as::<T>,
+        deserialize: deserialize_as::<T>,
+    });
+}
+
+impl Serialize for List {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.dotted_tail() {
+            Some(tail) => tagged(serializer, "dotted", &Dotted(self, tail)),
+            None => serializer.collect_seq(self.iter().map(Atom)),
+        }
+    }
+}
+
+// the elements of a dotted list followed by its tail
+struct Dotted<'a>(&'a List, &'a dyn ConsAny);
+
+impl Serialize for Dotted<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.iter().count() + 1))?;
+        for item in self.0.iter() {
+            seq.serialize_element(&Atom(item))?;
+        }
+        seq.serialize_element(&Atom(self.1))?;
+        seq.end()
+    }
+}
+
+impl Serialize for Cons {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let pair = [self.car_ref().map(Atom), self.cdr_ref().map(Atom)];
+        tagged(serializer, "cons", &pair)
+    }
+}
+
+fn tagged<S: Serializer, V: Serialize + ?Sized>(
+    serializer: S,
+    tag: &str,
+    value: &V,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(tag, value)?;
+    map.end()
+}
+
+struct Atom<'a>(&'a dyn ConsAny);
+
+impl Serialize for Atom<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let any = self.0.as_ref_any();
+        if let Some(list) = any.downcast_ref::<List>() {
+            return list.serialize(serializer);
+        }
+        if let Some(cons) = any.downcast_ref::<Cons>() {
+            return cons.serialize(serializer);
+        }
+        macro_rules! plain {
+            ($($t:ty),*) => {
+                $(
+                    if let Some(x) = any.downcast_ref::<$t>() {
+                        return x.serialize(serializer);
+                    }
+                )*
+            };
+        }
+        plain!(bool, i32, f64, String, &'static str);
+
+        if let Some(symbol) = any.downcast_ref::<Symbol>() {
+            return tagged(serializer, "symbol", symbol.as_str());
+        }
+        if let Some(x) = any.downcast_ref::<Rational64>() {
+            return tagged(serializer, "rational", &(x.numer(), x.denom()));
+        }
+        macro_rules! named {
+            ($($t:ident),*) => {
+                $(
+                    if let Some(x) = any.downcast_ref::<$t>() {
+                        return tagged(serializer, stringify!($t), x);
+                    }
+                )*
+            };
+        }
+        named!(char, i8, i16, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32);
+
+        match entry(self.0).and_then(|entry| entry.serde) {
+            Some(serde) => tagged(serializer, serde.tag, (serde.serialize)(self.0)),
+            None => Err(S::Error::custom(format!(
+                "no serde registered for {}",
+                self.0.type_name()
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for List {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let atom = deserializer.deserialize_any(AtomVisitor)?;
+        match (atom as Box<dyn Any>).downcast::<List>() {
+            Ok(list) => Ok(*list),
+            Err(_) => Err(de::Error::custom("expected a list")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Cons {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let atom = deserializer.deserialize_any(AtomVisitor)?;
+        match (atom as Box<dyn Any>).downcast::<Cons>() {
+            Ok(cons) => Ok(*cons),
+            Err(_) => Err(de::Error::custom("expected a cons")),
+        }
+    }
+}
+
+// an element read back, whatever its type
+struct AtomBox(Box<dyn ConsAny>);
+
+impl<'de> Deserialize<'de> for AtomBox {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AtomVisitor).map(AtomBox)
+    }
+}
+
+struct AtomVisitor;
+
+impl<'de> Visitor<'de> for AtomVisitor {
+    type Value = Box<dyn ConsAny>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list element")
+    }
+
+    fn visit_bool<E: de::Error>(self, b: bool) -> Result<Self::Value, E> {
+        Ok(Box::new(b))
+    }
+
+    // an integer is an i32 when it fits
+    fn visit_i64<E: de::Error>(self, n: i64) -> Result<Self::Value, E> {
+        Ok(match i32::try_from(n) {
+            Ok(n) => Box::new(n),
+            Err(_) => Box::new(n),
+        })
+    }
+
+    fn visit_u64<E: de::Error>(self, n: u64) -> Result<Self::Value, E> {
+        match i64::try_from(n) {
+            Ok(n) => self.visit_i64(n),
+            Err(_) => Ok(Box::new(n)),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, n: f64) -> Result<Self::Value, E> {
+        Ok(Box::new(n))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        Ok(Box::new(s.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, s: String) -> Result<Self::Value, E> {
+        Ok(Box::new(s))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = vec![];
+        while let Some(AtomBox(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Box::new(items.into_iter().collect::<List>()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let error = || de::Error::custom("expected a map with a single tag");
+        let tag: String = map.next_key()?.ok_or_else(error)?;
+        let atom = tagged_value(&tag, &mut map)?;
+        match map.next_key::<IgnoredAny>()? {
+            Some(_) => Err(error()),
+            None => Ok(atom),
+        }
+    }
+}
+
+fn tagged_value<'de, A: MapAccess<'de>>(
+    tag: &str,
+    map: &mut A,
+) -> Result<Box<dyn ConsAny>, A::Error> {
+    macro_rules! named {
+        ($($t:ident),*) => {
+            match tag {
+                $( stringify!($t) => return Ok(Box::new(map.next_value::<$t>()?)), )*
+                _ => {}
+            }
+        };
+    }
+    named!(char, i8, i16, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32);
+    Ok(match tag {
+        "symbol" => Box::new(Symbol::new(&map.next_value::<String>()?)),
+        "rational" => {
+            let (numer, denom) = map.next_value::<(i64, i64)>()?;
+            if denom == 0 {
+                return Err(de::Error::custom("rational with a zero denominator"));
+            }
+            Box::new(Rational64::new(numer, denom))
+        }
+        "dotted" => {
+            let mut items = map.next_value::<Vec<AtomBox>>()?;
+            let AtomBox(tail) = items
+                .pop()
+                .ok_or_else(|| de::Error::custom("dotted list without a tail"))?;
+            if items.is_empty() {
+                return Err(de::Error::custom("dotted list without elements"));
+            }
+            let mut list: List = items.into_iter().map(|AtomBox(item)| item).collect();
+            list.set_dotted_tail(tail);
+            Box::new(list)
+        }
+        "cons" => {
+            let [car, cdr] = map.next_value::<[Option<AtomBox>; 2]>()?;
+            let link = |atom: Option<AtomBox>| atom.map(|AtomBox(atom)| atom);
+            Box::new(Cons::new(link(car), link(cdr)))
+        }
+        tag => {
+            let serde = {
+                let types = types().read().unwrap();
+                let id = types.by_tag.get(tag);
+                id.and_then(|id| types.by_id[id].serde)
+            };
+            match serde {
+                Some(serde) => map.next_value_seed(Registered(serde.deserialize))?,
+                None => return Err(de::Error::custom(format!("unknown tag {}", tag))),
+            }
+        }
+    })
+}
+
+// reads a registered atom through its own Deserialize
+struct Registered(DeserializeFn);
+
+impl<'de> DeserializeSeed<'de> for Registered {
+    type Value = Box<dyn ConsAny>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut erased = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.0)(&mut erased).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    use super::*;
+    use crate::{
+        list, quote, sym,
+        utils::registry::{self, Key},
+    };
+
+    fn round_trip(list: &List) -> List {
+        let json = serde_json::to_string(list).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn json_lists() {
+        let l = list!(1, "a".to_string(), [sym!(b), 2.5], true, []);
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(json, r#"[1,"a",[{"symbol":"b"},2.5],true,[]]"#);
+        assert_eq!(round_trip(&l), l);
+
+        let l = list!(1, [2; 3]; 'x');
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(json, r#"{"dotted":[1,{"dotted":[2,3]},{"char":"x"}]}"#);
+        assert_eq!(round_trip(&l), l);
+
+        let l = list!(7i64, 1u8, 0.5f32, Rational64::new(1, 3), 3_000_000_000i64);
+        assert_eq!(round_trip(&l), l);
+        // a &str comes back as a String
+        assert_eq!(round_trip(&list!("s")), list!("s".to_string()));
+        // as does a plain integer that does not fit an i32
+        let big: List = serde_json::from_str("[3000000000]").unwrap();
+        assert_eq!(big, list!(3_000_000_000i64));
+
+        let long: List = (0..100_000).collect();
+        assert_eq!(round_trip(&long), long);
+        assert_eq!(round_trip(&List::nil()), List::nil());
+    }
+
+    #[test]
+    fn json_cons() {
+        let cons = Cons::new(Some(Box::new(1)), Some(Box::new(quote!((a)))));
+        let json = serde_json::to_string(&cons).unwrap();
+        assert_eq!(json, r#"{"cons":[1,[{"symbol":"a"}]]}"#);
+        assert_eq!(serde_json::from_str::<Cons>(&json).unwrap(), cons);
+        let empty: Cons = serde_json::from_str(r#"{"cons":[null,null]}"#).unwrap();
+        assert!(empty.car_ref().is_none());
+    }
+
+    // the same tokens for any format, not only JSON
+    #[test]
+    fn tokens() {
+        let l = list!(1, [sym!(a)], Rational64::new(1, 2); 'x');
+        assert_tokens(
+            &l,
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("dotted"),
+                Token::Seq { len: Some(4) },
+                Token::I32(1),
+                Token::Seq { len: None },
+                Token::Map { len: Some(1) },
+                Token::Str("symbol"),
+                Token::Str("a"),
+                Token::MapEnd,
+                Token::SeqEnd,
+                Token::Map { len: Some(1) },
+                Token::Str("rational"),
+                Token::Tuple { len: 2 },
+                Token::I64(1),
+                Token::I64(2),
+                Token::TupleEnd,
+                Token::MapEnd,
+                Token::Map { len: Some(1) },
+                Token::Str("char"),
+                Token::Char('x'),
+                Token::MapEnd,
+                Token::SeqEnd,
+                Token::MapEnd,
+            ],
+        );
+        assert_de_tokens_error::<List>(
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("nope"),
+                Token::I32(1),
+            ],
+            "unknown tag nope",
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn registered_atoms() {
+        let l = list!(Point { x: 1, y: 2 });
+        let error = serde_json::to_string(&list!([(1, 2)])).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("no serde registered for (i32, i32)"));
+
+        register::<Point>("point");
+        register::<Point>("point");
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(json, r#"[{"point":{"x":1,"y":2}}]"#);
+        assert_eq!(round_trip(&l), l);
+
+        let bad = serde_json::from_str::<List>(r#"[{"point":{"x":1}}]"#);
+        assert!(bad.unwrap_err().to_string().contains("missing field `y`"));
+        assert!(serde_json::from_str::<List>(r#"[{"nope":1}]"#).is_err());
+        assert!(serde_json::from_str::<List>("1").is_err());
+        assert!(serde_json::from_str::<List>(r#"{"dotted":[1]}"#).is_err());
+
+        // a registered atom goes through its own Serialize, and the
+        // order registry has the same entry
+        assert_tokens(
+            &l,
+            &[
+                Token::Seq { len: None },
+                Token::Map { len: Some(1) },
+                Token::Str("point"),
+                Token::Struct {
+                    name: "Point",
+                    len: 2,
+                },
+                Token::Str("x"),
+                Token::I32(1),
+                Token::Str("y"),
+                Token::I32(2),
+                Token::StructEnd,
+                Token::MapEnd,
+                Token::SeqEnd,
+            ],
+        );
+        registry::register::<Point>();
+        assert!(Key(list!(Point { x: 0, y: 5 })) < Key(l.clone()));
+        assert_eq!(round_trip(&l), l);
+    }
+}
@@ -1,12 +1,17 @@
 use std::{
     alloc::{self, Layout},
+    cmp::Ordering,
     fmt::Debug,
     marker::PhantomData,
-    mem,
+    mem, panic,
     ptr::{self, NonNull},
+    thread,
 };
 
 type Link<T> = Option<NonNull<Node<T>>>;
+// first node, last node and count of a detached chain of nodes
+type Chain<T> = (NonNull<Node<T>>, NonNull<Node<T>>, usize);
+
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
@@ -92,6 +97,166 @@ impl<T> List<T> {
             node
         })
     }
+
+    // node must be in this list. the node is not freed, its own
+    // prev/next are left as they were
+    unsafe fn unlink_node(&mut self, node: NonNull<Node<T>>) {
+        let node = &mut *node.as_ptr();
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.len -= 1;
+    }
+
+    // links the chain start..=end of len nodes between prev and next,
+    // which must be neighbours in this list (None for the ends)
+    unsafe fn splice_nodes(
+        &mut self,
+        prev: Link<T>,
+        next: Link<T>,
+        start: NonNull<Node<T>>,
+        end: NonNull<Node<T>>,
+        len: usize,
+    ) {
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(start),
+            None => self.head = Some(start),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(end),
+            None => self.tail = Some(end),
+        }
+        (*start.as_ptr()).prev = prev;
+        (*end.as_ptr()).next = next;
+        self.len += len;
+    }
+
+    // takes the whole chain out, leaving the list empty
+    fn detach_all_nodes(&mut self) -> Option<Chain<T>> {
+        let head = self.head.take()?;
+        let tail = self.tail.take()?;
+        Some((head, tail, mem::replace(&mut self.len, 0)))
+    }
+
+    // returns the nodes before node, which is at index at. on None
+    // (past the end) returns the whole list
+    unsafe fn split_off_before_node(&mut self, node: Link<T>, at: usize) -> Self {
+        let Some(node) = node else {
+            return mem::take(self);
+        };
+        let first_tail = (*node.as_ptr()).prev.take();
+        let first_head = match first_tail {
+            Some(tail) => {
+                (*tail.as_ptr()).next = None;
+                self.head
+            }
+            None => None,
+        };
+        self.head = Some(node);
+        self.len -= at;
+        List {
+            head: first_head,
+            tail: first_tail,
+            len: at,
+            marker: PhantomData,
+        }
+    }
+
+    // returns the nodes after node, keeping the first at nodes. on None
+    // (past the end) returns the whole list
+    unsafe fn split_off_after_node(&mut self, node: Link<T>, at: usize) -> Self {
+        let Some(node) = node else {
+            return mem::take(self);
+        };
+        let second_head = (*node.as_ptr()).next.take();
+        let second_tail = match second_head {
+            Some(head) => {
+                (*head.as_ptr()).prev = None;
+                self.tail
+            }
+            None => None,
+        };
+        self.tail = Some(node);
+        let second = List {
+            head: second_head,
+            tail: second_tail,
+            len: self.len - at,
+            marker: PhantomData,
+        };
+        self.len = at;
+        second
+    }
+
+    // walks from whichever end is nearer
+    fn node_at(&self, at: usize) -> Link<T> {
+        if at >= self.len {
+            return None;
+        }
+        unsafe {
+            if at < self.len / 2 {
+                let mut node = self.head;
+                for _ in 0..at {
+                    node = (*node?.as_ptr()).next;
+                }
+                node
+            } else {
+                let mut node = self.tail;
+                for _ in at + 1..self.len {
+                    node = (*node?.as_ptr()).prev;
+                }
+                node
+            }
+        }
+    }
+
+    // top down merge sort that only moves nodes between lists, so no
+    // element is copied and nothing is allocated. every node is always
+    // owned by exactly one list, and MergeGuard puts the unmerged
+    // halves back if compare panics, so a panic leaves all elements in
+    // self in some order, like slice::sort does
+    fn merge_sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: &mut F) {
+        struct MergeGuard<'a, T> {
+            merged: &'a mut List<T>,
+            left: List<T>,
+            right: List<T>,
+        }
+
+        impl<T> Drop for MergeGuard<'_, T> {
+            fn drop(&mut self) {
+                self.merged.append(&mut self.left);
+                self.merged.append(&mut self.right);
+            }
+        }
+
+        if self.len < 2 {
+            return;
+        }
+        let right = self.split_off(self.len / 2);
+        let left = mem::take(self);
+        let mut guard = MergeGuard {
+            merged: self,
+            left,
+            right,
+        };
+        guard.left.merge_sort_by(compare);
+        guard.right.merge_sort_by(compare);
+
+        // equal elements are taken from the left first, which keeps
+        // the sort stable
+        while let (Some(a), Some(b)) = (guard.left.front(), guard.right.front()) {
+            let node = if compare(a, b) == Ordering::Greater {
+                guard.right.pop_front_node()
+            } else {
+                guard.left.pop_front_node()
+            };
+            guard.merged.push_back_node(node.unwrap());
+        }
+    }
 }
 
 unsafe impl<#[may_dangle] T> Drop for List<T> {
@@ -412,6 +577,249 @@ impl<T> ExactSizeIterator for IntoIter<T> {
     }
 }
 
+// a cursor points at an element, or at the "ghost" non-element
+// between the tail and the head. moving past either end lands on the
+// ghost, and moving on from the ghost wraps around to the other end.
+// this is how a linked list earns its keep: editing at the cursor is
+// O(1), no walking from the head
+pub struct Cursor<'a, T: 'a> {
+    index: usize,
+    current: Link<T>,
+    list: &'a List<T>,
+}
+
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
+    }
+}
+
+impl<T: Debug> Debug for Cursor<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    // None on the ghost
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).next;
+                self.index += 1;
+            },
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len().saturating_sub(1);
+            }
+            // index wraps to len on the ghost
+            Some(node) => unsafe {
+                self.current = (*node.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len());
+            },
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.current.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            None => self.list.head,
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        };
+        next.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        prev.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+}
+
+pub struct CursorMut<'a, T: 'a> {
+    index: usize,
+    current: Link<T>,
+    list: &'a mut List<T>,
+}
+
+impl<T: Debug> Debug for CursorMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.as_cursor().index()
+    }
+
+    pub fn move_next(&mut self) {
+        let mut cursor = self.as_cursor();
+        cursor.move_next();
+        (self.index, self.current) = (cursor.index, cursor.current);
+    }
+
+    pub fn move_prev(&mut self) {
+        let mut cursor = self.as_cursor();
+        cursor.move_prev();
+        (self.index, self.current) = (cursor.index, cursor.current);
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            None => self.list.head,
+            Some(node) => unsafe { (*node.as_ptr()).next },
+        };
+        next.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+        };
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    // a read only cursor at the same place, borrowing this one
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.index,
+            current: self.current,
+            list: self.list,
+        }
+    }
+
+    // on the ghost this inserts at the front
+    pub fn insert_after(&mut self, elem: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node::new(elem))));
+        let next = match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { (*current.as_ptr()).next },
+        };
+        unsafe { self.list.splice_nodes(self.current, next, node, node, 1) };
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    // on the ghost this inserts at the back
+    pub fn insert_before(&mut self, elem: T) {
+        let node = NonNull::from(Box::leak(Box::new(Node::new(elem))));
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(current) => unsafe { (*current.as_ptr()).prev },
+        };
+        unsafe { self.list.splice_nodes(prev, self.current, node, node, 1) };
+        self.index += 1;
+    }
+
+    // the cursor moves on to the next element
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            self.current = (*node.as_ptr()).next;
+            self.list.unlink_node(node);
+            Some(Box::from_raw(node.as_ptr()).into_element())
+        }
+    }
+
+    // moves all of list in after the current element, or at the front
+    // on the ghost
+    pub fn splice_after(&mut self, mut list: List<T>) {
+        let Some((start, end, len)) = list.detach_all_nodes() else {
+            return;
+        };
+        let next = match self.current {
+            None => self.list.head,
+            Some(current) => unsafe { (*current.as_ptr()).next },
+        };
+        unsafe { self.list.splice_nodes(self.current, next, start, end, len) };
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    // moves all of list in before the current element, or at the back
+    // on the ghost
+    pub fn splice_before(&mut self, mut list: List<T>) {
+        let Some((start, end, len)) = list.detach_all_nodes() else {
+            return;
+        };
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(current) => unsafe { (*current.as_ptr()).prev },
+        };
+        unsafe { self.list.splice_nodes(prev, self.current, start, end, len) };
+        self.index += len;
+    }
+
+    // everything after the current element as a new list. on the
+    // ghost that is the whole list
+    pub fn split_after(&mut self) -> List<T> {
+        let at = if self.current.is_none() {
+            self.index = 0;
+            self.list.len
+        } else {
+            self.index + 1
+        };
+        unsafe { self.list.split_off_after_node(self.current, at) }
+    }
+
+    // everything before the current element as a new list. on the
+    // ghost that is the whole list
+    pub fn split_before(&mut self) -> List<T> {
+        let at = mem::replace(&mut self.index, 0);
+        unsafe { self.list.split_off_before_node(self.current, at) }
+    }
+}
+
+// the raw pointers make the compiler give up on Send and Sync, but a
+// List owns its nodes like a Box<Node<T>> would, and never shares one
+// between two lists, so it is Send and Sync exactly when T is
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
+
+// the iterators and cursors are borrows: &T for Iter and Cursor,
+// &mut T for IterMut and CursorMut
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+unsafe impl<T: Sync> Send for Cursor<'_, T> {}
+unsafe impl<T: Sync> Sync for Cursor<'_, T> {}
+unsafe impl<T: Send> Send for CursorMut<'_, T> {}
+unsafe impl<T: Sync> Sync for CursorMut<'_, T> {}
+
 // public
 impl<T> List<T> {
     #[inline]
@@ -489,14 +897,226 @@ impl<T> List<T> {
         // *self = Self::new(); this is fine
         while self.pop_front().is_some() {}
     }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    // moves all of other to the back of self in O(1), other is left empty
+    pub fn append(&mut self, other: &mut Self) {
+        if let Some((start, end, len)) = other.detach_all_nodes() {
+            unsafe { self.splice_nodes(self.tail, None, start, end, len) }
+        }
+    }
+
+    // self keeps [0, at), the rest is returned. panics if at > len
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "split_off: index {} > len {}", at, self.len);
+        match at {
+            0 => mem::take(self),
+            _ => unsafe { self.split_off_after_node(self.node_at(at - 1), at) },
+        }
+    }
+
+    // panics if at >= len
+    pub fn remove(&mut self, at: usize) -> T {
+        assert!(at < self.len, "remove: index {} >= len {}", at, self.len);
+        let node = self.node_at(at).unwrap();
+        unsafe {
+            self.unlink_node(node);
+            Box::from_raw(node.as_ptr()).into_element()
+        }
+    }
+
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|elem| elem == x)
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut cursor = self.cursor_front_mut();
+        while let Some(elem) = cursor.current() {
+            if f(elem) {
+                cursor.move_next();
+            } else {
+                cursor.remove_current();
+            }
+        }
+    }
+
+    // removes consecutive elements that same_bucket(elem, previous)
+    // says are duplicates, keeping the first of each run
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let Some(mut prev) = self.head else {
+            return;
+        };
+        let mut node = unsafe { (*prev.as_ptr()).next };
+        while let Some(current) = node {
+            unsafe {
+                node = (*current.as_ptr()).next;
+                if same_bucket(&mut (*current.as_ptr()).elem, &mut (*prev.as_ptr()).elem) {
+                    // unlinked first, a panicking drop leaves the list whole
+                    self.unlink_node(current);
+                    drop(Box::from_raw(current.as_ptr()));
+                } else {
+                    prev = current;
+                }
+            }
+        }
+    }
+
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    // stable, O(n log n), relinks the nodes in place
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        self.merge_sort_by(&mut compare)
+    }
+
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut key: F) {
+        self.sort_by(|a, b| key(a).cmp(&key(b)))
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp)
+    }
+}
+
+// parallel, on scoped threads. the list is cut into one run of nodes
+// per thread by walking it once, each thread iterates its own run in
+// place, and the per thread results are put back together in order.
+// a panic in f is passed on once every thread has stopped
+impl<T: Sync> List<T> {
+    // at most n Iters of nearly equal length, front to back
+    fn chunks(&self, n: usize) -> Vec<Iter<'_, T>> {
+        let (size, extra) = (self.len / n, self.len % n);
+        let mut chunks = vec![];
+        let mut node = self.head;
+        for i in 0..n {
+            let len = size + usize::from(i < extra);
+            if len == 0 {
+                break;
+            }
+            let head = node;
+            let mut tail = node;
+            for _ in 1..len {
+                tail = tail.and_then(|node| unsafe { (*node.as_ptr()).next });
+            }
+            node = tail.and_then(|node| unsafe { (*node.as_ptr()).next });
+            chunks.push(Iter {
+                head,
+                tail,
+                len,
+                marker: PhantomData,
+            });
+        }
+        chunks
+    }
+
+    fn par_chunks<R, F>(&self, f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(Iter<'_, T>) -> R + Sync,
+    {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunks = self.chunks(threads.min(self.len).max(1));
+        let f = &f;
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunks
+                .into_iter()
+                .map(|chunk| scope.spawn(move || f(chunk)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        })
+    }
+
+    pub fn par_for_each<F: Fn(&T) + Sync>(&self, f: F) {
+        self.par_chunks(|chunk| chunk.for_each(&f));
+    }
+
+    // the result keeps the order of self
+    pub fn par_map<U: Send, F: Fn(&T) -> U + Sync>(&self, f: F) -> List<U> {
+        let mut mapped = List::new();
+        for mut chunk in self.par_chunks(|chunk| chunk.map(&f).collect::<List<U>>()) {
+            mapped.append(&mut chunk);
+        }
+        mapped
+    }
+
+    // each thread folds its run starting from init(), then the results
+    // are combined front to back, so combine only has to be associative
+    pub fn par_fold<A, I, F, C>(&self, init: I, fold: F, combine: C) -> A
+    where
+        A: Send,
+        I: Fn() -> A + Sync,
+        F: Fn(A, &T) -> A + Sync,
+        C: FnMut(A, A) -> A,
+    {
+        self.par_chunks(|chunk| chunk.fold(init(), &fold))
+            .into_iter()
+            .reduce(combine)
+            .unwrap_or_else(init)
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::cell::Cell;
+    use std::collections::LinkedList;
     use std::fmt::Debug;
-    use std::panic;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
 
-    use super::List;
+    use rand::{seq::SliceRandom, Rng};
+    use test::Bencher;
+
+    use super::{Cursor, CursorMut, IntoIter, Iter, IterMut, List};
 
     #[test]
     fn basics() {
@@ -647,4 +1267,393 @@ mod test {
         assert_eq!(into_iter.next(), Some(4));
         assert_eq!(into_iter.next(), None);
     }
+
+    // walks the raw links both ways, so a bad prev/next or len shows
+    // up even where the iterators would not notice
+    fn check_links<T: PartialEq + Debug + Clone>(list: &List<T>) -> Vec<T> {
+        let mut forward = vec![];
+        let mut node = list.head;
+        let mut prev = None;
+        while let Some(current) = node {
+            let current = unsafe { &*current.as_ptr() };
+            assert_eq!(current.prev, prev);
+            forward.push(current.elem.clone());
+            prev = node;
+            node = current.next;
+        }
+        assert_eq!(list.tail, prev);
+        assert_eq!(forward.len(), list.len());
+        forward
+    }
+
+    fn from(elems: &[i32]) -> List<i32> {
+        elems.iter().copied().collect()
+    }
+
+    #[test]
+    fn cursor_move() {
+        let list = from(&[1, 2, 3]);
+        let mut cursor = list.cursor_front();
+        assert_eq!((cursor.current(), cursor.index()), (Some(&1), Some(0)));
+        assert_eq!(cursor.peek_prev(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!((cursor.current(), cursor.index()), (Some(&3), Some(2)));
+        // off the end onto the ghost, then around to the front
+        cursor.move_next();
+        assert_eq!((cursor.current(), cursor.index()), (None, None));
+        assert_eq!(
+            (cursor.peek_next(), cursor.peek_prev()),
+            (Some(&1), Some(&3))
+        );
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&1));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!((cursor.current(), cursor.index()), (Some(&3), Some(2)));
+
+        let empty = List::<i32>::new();
+        let mut cursor = empty.cursor_back();
+        cursor.move_prev();
+        assert_eq!((cursor.current(), cursor.index()), (None, None));
+    }
+
+    #[test]
+    fn cursor_edit() {
+        let mut list = from(&[1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(10);
+        cursor.insert_after(20);
+        assert_eq!(cursor.index(), Some(2));
+        *cursor.current().unwrap() *= 100;
+        assert_eq!(cursor.remove_current(), Some(200));
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 20));
+        assert_eq!(check_links(&list), [1, 10, 20, 3]);
+
+        // at the ghost, after is the front and before is the back
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        cursor.insert_after(0);
+        cursor.insert_before(4);
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(5));
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(check_links(&list), [0, 1, 10, 20, 3]);
+
+        let mut list = from(&[1]);
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.remove_current(), Some(1));
+        cursor.insert_before(2);
+        assert_eq!(check_links(&list), [2]);
+    }
+
+    #[test]
+    fn cursor_split_splice() {
+        let mut list = from(&[1, 2, 3, 4, 5]);
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let after = cursor.split_after();
+        let before = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(check_links(&before), [1, 2]);
+        assert_eq!(check_links(&after), [4, 5]);
+
+        cursor.splice_after(after);
+        cursor.splice_before(before);
+        cursor.splice_before(List::new());
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(check_links(&list), [1, 2, 3, 4, 5]);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_prev();
+        cursor.splice_after(from(&[-1, 0]));
+        cursor.splice_before(from(&[6]));
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(7));
+        assert_eq!(check_links(&list), [-1, 0, 1, 2, 3, 4, 5, 6]);
+
+        // splitting at the ghost takes everything
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        let all = cursor.split_after();
+        assert_eq!(cursor.split_before().len(), 0);
+        assert!(list.is_empty());
+        assert_eq!(all.len(), 8);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_before(all);
+        assert_eq!(check_links(&list).len(), 8);
+    }
+
+    #[test]
+    fn cursor_panic_drop() {
+        // like panic_test, but every element counts its drop, so a
+        // list split or spliced by a cursor can be checked to neither
+        // leak nor double free when one destructor panics
+        struct Counted<'a>(i32, &'a Cell<i32>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+                if self.0 == 2 {
+                    panic!("intent panic");
+                }
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut list: List<_> = (0..6).map(|i| Counted(i, &drops)).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let before = cursor.split_before();
+        cursor.move_next();
+        let after = cursor.split_after();
+        cursor.splice_after(after);
+        cursor.splice_before(before);
+        cursor.move_next();
+        let removed = cursor.remove_current();
+        assert_eq!(removed.as_ref().map(|x| x.0), Some(3));
+        drop(removed);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(list)));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn append_split_remove() {
+        let mut list = from(&[1, 2]);
+        let mut other = from(&[3, 4, 5]);
+        list.append(&mut other);
+        list.append(&mut List::new());
+        assert!(other.is_empty());
+        assert_eq!(check_links(&list), [1, 2, 3, 4, 5]);
+
+        let tail = list.split_off(3);
+        assert_eq!(check_links(&tail), [4, 5]);
+        assert_eq!(check_links(&list.split_off(3)), Vec::<i32>::new());
+        assert_eq!(check_links(&list), [1, 2, 3]);
+
+        assert_eq!(list.remove(1), 2);
+        assert_eq!(list.remove(1), 3);
+        assert_eq!(check_links(&list), [1]);
+        assert!(list.contains(&1) && !list.contains(&2));
+        assert_eq!(check_links(&list.split_off(0)), [1]);
+        assert!(list.is_empty());
+
+        let result = panic::catch_unwind(|| from(&[1]).split_off(2));
+        assert!(result.is_err());
+        let result = panic::catch_unwind(|| from(&[1]).remove(1));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn retain_dedup() {
+        let mut list = from(&[1, 2, 3, 4, 5, 6]);
+        list.retain(|x| x % 2 == 0);
+        assert_eq!(check_links(&list), [2, 4, 6]);
+        list.retain(|_| false);
+        assert_eq!(check_links(&list), Vec::<i32>::new());
+
+        let mut list = from(&[1, 1, 2, 3, 3, 3, 1, 1]);
+        list.dedup();
+        assert_eq!(check_links(&list), [1, 2, 3, 1]);
+        let mut list = from(&[10, 11, 20, 35, 31, 30]);
+        list.dedup_by_key(|x| *x / 10);
+        assert_eq!(check_links(&list), [10, 20, 35]);
+    }
+
+    #[test]
+    fn sort() {
+        let mut list = from(&[5, 3, 9, 1, 3, 0, 7]);
+        list.sort();
+        assert_eq!(check_links(&list), [0, 1, 3, 3, 5, 7, 9]);
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(check_links(&list), [9, 7, 5, 3, 3, 1, 0]);
+
+        // stable: equal keys keep their order
+        let mut list: List<_> = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]
+            .into_iter()
+            .collect();
+        list.sort_by_key(|x| x.0);
+        assert_eq!(check_links(&list), [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+
+        let mut rng = rand::thread_rng();
+        for len in [0, 1, 2, 33, 500] {
+            let mut v: Vec<i32> = (0..len).map(|_| rng.gen_range(0..50)).collect();
+            let mut list: List<_> = v.iter().copied().collect();
+            let nodes: Vec<_> = list.iter().map(|x| x as *const i32).collect();
+            list.sort();
+            v.sort();
+            assert_eq!(check_links(&list), v);
+            // relinked, not reallocated
+            let mut sorted: Vec<_> = list.iter().map(|x| x as *const i32).collect();
+            sorted.sort();
+            let mut nodes = nodes;
+            nodes.sort();
+            assert_eq!(sorted, nodes);
+        }
+    }
+
+    #[test]
+    fn sort_panic() {
+        // a comparison that panics half way must not lose, leak or
+        // double free any element
+        let drops = Cell::new(0);
+        struct Counted<'a>(i32, &'a Cell<i32>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+
+        let mut list: List<_> = (0..100).rev().map(|i| Counted(i, &drops)).collect();
+        let mut compares = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                compares += 1;
+                if compares == 200 {
+                    panic!("intent panic");
+                }
+                a.0.cmp(&b.0)
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 0);
+        let mut elems: Vec<_> = list.iter().map(|x| x.0).collect();
+        assert_eq!(elems.len(), 100);
+        elems.sort();
+        assert_eq!(elems, (0..100).collect::<Vec<_>>());
+        drop(list);
+        assert_eq!(drops.get(), 100);
+    }
+
+    fn bench_data() -> Vec<i32> {
+        let mut v: Vec<i32> = (0..10_000).collect();
+        v.shuffle(&mut rand::thread_rng());
+        v
+    }
+
+    #[bench]
+    fn bench_sort_std_list(b: &mut Bencher) {
+        let v = bench_data();
+        b.iter(|| {
+            let mut list: List<_> = v.iter().copied().collect();
+            list.sort();
+            list
+        });
+    }
+
+    #[bench]
+    fn bench_sort_vec(b: &mut Bencher) {
+        let v = bench_data();
+        b.iter(|| {
+            let mut v = v.clone();
+            v.sort();
+            v
+        });
+    }
+
+    // LinkedList has no sort, the usual way is through a Vec
+    #[bench]
+    fn bench_sort_linked_list(b: &mut Bencher) {
+        let v = bench_data();
+        b.iter(|| {
+            let list: LinkedList<_> = v.iter().copied().collect();
+            let mut v: Vec<_> = list.into_iter().collect();
+            v.sort();
+            v.into_iter().collect::<LinkedList<_>>()
+        });
+    }
+
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    // not_send::<T>() only compiles if T is not Send: for a Send T both
+    // impls apply and the call is ambiguous. same for not_sync
+    trait AmbiguousIfSend<A> {
+        fn check() {}
+    }
+    impl<T: ?Sized> AmbiguousIfSend<()> for T {}
+    impl<T: ?Sized + Send> AmbiguousIfSend<u8> for T {}
+
+    trait AmbiguousIfSync<A> {
+        fn check() {}
+    }
+    impl<T: ?Sized> AmbiguousIfSync<()> for T {}
+    impl<T: ?Sized + Sync> AmbiguousIfSync<u8> for T {}
+
+    #[test]
+    fn send_sync() {
+        is_send::<List<i32>>();
+        is_sync::<List<i32>>();
+        is_send::<IntoIter<i32>>();
+        is_send::<Iter<'_, i32>>();
+        is_sync::<Iter<'_, i32>>();
+        is_send::<IterMut<'_, i32>>();
+        is_sync::<IterMut<'_, i32>>();
+        is_send::<Cursor<'_, i32>>();
+        is_sync::<Cursor<'_, i32>>();
+        is_send::<CursorMut<'_, i32>>();
+        is_sync::<CursorMut<'_, i32>>();
+        // Rc is neither, Cell is Send but not Sync
+        <List<Rc<i32>> as AmbiguousIfSend<_>>::check();
+        <List<Rc<i32>> as AmbiguousIfSync<_>>::check();
+        <Iter<'_, Cell<i32>> as AmbiguousIfSend<_>>::check();
+        <List<Cell<i32>> as AmbiguousIfSync<_>>::check();
+        is_send::<List<Cell<i32>>>();
+
+        let list = from(&[1, 2, 3]);
+        let list = thread::spawn(move || {
+            let mut list = list;
+            list.push_back(4);
+            list
+        })
+        .join()
+        .unwrap();
+        thread::scope(|scope| {
+            scope.spawn(|| assert_eq!(list.iter().sum::<i32>(), 10));
+            scope.spawn(|| assert_eq!(list.len(), 4));
+        });
+    }
+
+    #[test]
+    fn parallel() {
+        for len in [0, 1, 7, 1000] {
+            let list: List<i32> = (0..len).collect();
+            let count = AtomicUsize::new(0);
+            list.par_for_each(|x| {
+                count.fetch_add(*x as usize, Ordering::Relaxed);
+            });
+            let sum = (0..len).sum::<i32>();
+            assert_eq!(count.into_inner(), sum as usize);
+
+            let squares = list.par_map(|x| x * x);
+            assert_eq!(
+                check_links(&squares),
+                list.iter().map(|x| x * x).collect::<Vec<_>>()
+            );
+
+            let total = list.par_fold(|| 0, |acc, x| acc + x, |a, b| a + b);
+            assert_eq!(total, sum);
+            // combined in order, so a non commutative combine works
+            let joined = list.par_fold(String::new, |acc, x| acc + &x.to_string(), |a, b| a + &b);
+            assert_eq!(
+                joined,
+                list.iter().map(|x| x.to_string()).collect::<String>()
+            );
+        }
+
+        let list = from(&[1, 2, 3]);
+        let result = panic::catch_unwind(|| list.par_for_each(|x| assert_ne!(*x, 2)));
+        assert!(result.is_err());
+    }
 }
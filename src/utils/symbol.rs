@@ -0,0 +1,140 @@
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    ptr,
+    sync::{Mutex, OnceLock},
+};
+
+// scheme symbol atom, e.g. `foo` in '(foo 1 2).
+// names are interned, every Symbol of the same name points to the
+// same str, so comparing and hashing a symbol is O(1)
+#[derive(Debug, Clone, Copy)]
+pub struct Symbol(&'static str);
+
+// interned names live for the rest of the program
+fn intern(name: &str) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut names = NAMES.get_or_init(Default::default).lock().unwrap();
+    match names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.into());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+impl Symbol {
+    pub fn new(name: &str) -> Self {
+        Self(intern(name))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.0, state)
+    }
+}
+
+// alphabetical, not by address, so sorting is deterministic
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+// sym!(foo) is the symbol foo, names that are not a single rust
+// token are written as a string: sym!("set-car!")
+#[macro_export]
+macro_rules! sym {
+    ($name:literal) => {
+        $crate::utils::symbol::Symbol::new($name)
+    };
+    ($name:tt) => {
+        $crate::utils::symbol::Symbol::new(stringify!($name))
+    };
+}
+
+// quote!((a (b 1) "s")) is '(a (b 1) "s"): identifiers and operators
+// become symbols, literals stay values and parens nest lists.
+// a name like set-car! or -1 is several rust tokens, each one an element
+#[macro_export]
+macro_rules! quote {
+    (()) => {
+        $crate::utils::list::List::nil()
+    };
+    (( $( $item:tt )+ )) => {
+        [$( Box::new($crate::quote!($item)) as Box<dyn $crate::utils::cons::ConsAny> ),+]
+            .into_iter()
+            .collect::<$crate::utils::list::List>()
+    };
+    ($atom:literal) => {
+        $atom
+    };
+    ($atom:tt) => {
+        $crate::sym!($atom)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{list, utils::list::List};
+
+    #[test]
+    fn interned() {
+        let a = Symbol::new("foo");
+        let name = String::from("foo");
+        let b = Symbol::from(name.as_str());
+        assert_eq!(a, b);
+        assert!(ptr::eq(a.as_str(), b.as_str()));
+        assert_ne!(a, Symbol::new("bar"));
+        assert!(Symbol::new("bar") < a);
+        assert_eq!(sym!(foo), a);
+        assert_eq!(sym!("set-car!").to_string(), "set-car!");
+        assert_eq!(sym!(+).as_str(), "+");
+    }
+
+    #[test]
+    fn quote_list() {
+        let l = quote!((a (b 1) "s" ()));
+        assert_eq!(l.to_string(), "(a (b 1) \"s\" ())");
+        assert_eq!(l, list!(sym!(a), list!(sym!(b), 1), "s", List::nil()));
+        assert_eq!(quote!(()), List::nil());
+        assert_eq!(quote!(x), sym!(x));
+        assert_eq!(quote!((* (+ 1 2) 3)), List::parse("(* (+ 1 2) 3)").unwrap());
+    }
+}
@@ -0,0 +1,350 @@
+use super::{
+    cons::{into_box, ConsAny},
+    list::{DottedIterator, Iter, List},
+};
+
+// a tree is a List whose elements are either subtrees (Lists) or
+// leaves of any other type. '() as an element is an empty subtree, not
+// a leaf, and the tail of a dotted list is a leaf like the cars.
+// recursion only goes as deep as the nesting, never along a list
+
+// lazy walks over a tree, borrowing it. the elements of the tree
+// itself are at depth 1, the elements of a subtree one deeper:
+//
+// (1 (2 3))   pre-order  1 (2 3) 2 3
+//             post-order 1 2 3 (2 3)
+//             leaves     1 2 3
+pub fn pre_order(tree: &List) -> TreeIter<'_> {
+    TreeIter::new(tree, Order::Pre)
+}
+
+pub fn post_order(tree: &List) -> TreeIter<'_> {
+    TreeIter::new(tree, Order::Post)
+}
+
+pub fn leaves(tree: &List) -> TreeIter<'_> {
+    TreeIter::new(tree, Order::Leaves)
+}
+
+// compares the leaves one by one and stops at the first difference,
+// the shapes of a and b don't matter
+pub fn same_fringe(a: &List, b: &List) -> bool {
+    leaves(a).eq(leaves(b))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Order {
+    Pre,
+    Post,
+    Leaves,
+}
+
+#[derive(Debug)]
+pub struct TreeIter<'a> {
+    order: Order,
+    // the rest of every list on the path to the current element,
+    // with the subtree it walks. the tree itself has no subtree
+    stack: Vec<(Iter<'a>, Option<&'a List>)>,
+}
+
+impl<'a> TreeIter<'a> {
+    fn new(tree: &'a List, order: Order) -> Self {
+        Self {
+            order,
+            stack: vec![(tree.iter(), None)],
+        }
+    }
+
+    // yields (depth, element)
+    pub fn with_depth(self) -> WithDepth<'a> {
+        WithDepth { iter: self }
+    }
+
+    fn next_with_depth(&mut self) -> Option<(usize, &'a dyn ConsAny)> {
+        loop {
+            let depth = self.stack.len();
+            let (iter, _) = self.stack.last_mut()?;
+            match iter.next() {
+                Some(item) => match item.cast_ref::<List>() {
+                    Some(subtree) => {
+                        self.stack.push((subtree.iter(), Some(subtree)));
+                        if self.order == Order::Pre {
+                            return Some((depth, item));
+                        }
+                    }
+                    None => return Some((depth, item)),
+                },
+                None => {
+                    // a dotted tail is a leaf after the cars
+                    if let Some(tail) = iter.take_tail() {
+                        return Some((depth, tail));
+                    }
+                    let (_, subtree) = self.stack.pop().unwrap();
+                    if let (Order::Post, Some(subtree)) = (self.order, subtree) {
+                        return Some((depth - 1, subtree));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for TreeIter<'a> {
+    type Item = &'a dyn ConsAny;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_depth().map(|(_, item)| item)
+    }
+}
+
+#[derive(Debug)]
+pub struct WithDepth<'a> {
+    iter: TreeIter<'a>,
+}
+
+impl<'a> Iterator for WithDepth<'a> {
+    type Item = (usize, &'a dyn ConsAny);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_with_depth()
+    }
+}
+
+// (map-tree f tree), same shape with every leaf replaced by f(leaf)
+pub fn tree_map<T: ConsAny>(tree: &List, mut f: impl FnMut(&dyn ConsAny) -> T) -> List {
+    fn map<T: ConsAny>(tree: &List, f: &mut dyn FnMut(&dyn ConsAny) -> T) -> List {
+        let mut iter = tree.iter();
+        let mut mapped: List = iter
+            .by_ref()
+            .map(|item| match item.cast_ref::<List>() {
+                Some(subtree) => Box::new(map(subtree, f)),
+                None => into_box(f(item)),
+            })
+            .collect();
+        if let Some(tail) = iter.take_tail() {
+            mapped.set_dotted_tail(f(tail));
+        }
+        mapped
+    }
+    map(tree, &mut f)
+}
+
+// folds the leaves from left to right
+pub fn tree_fold<A>(tree: &List, init: A, mut f: impl FnMut(A, &dyn ConsAny) -> A) -> A {
+    leaves(tree).fold(init, f)
+}
+
+pub fn count_leaves(tree: &List) -> usize {
+    tree_fold(tree, 0, |count, _| count + 1)
+}
+
+// levels of nesting: '() is 0, a list of leaves 1, ((1) 2) is 2
+pub fn depth(tree: &List) -> usize {
+    if tree.is_null() {
+        return 0;
+    }
+    let subtrees = tree.iter().filter_map(|item| item.cast_ref::<List>());
+    1 + subtrees.map(depth).max().unwrap_or(0)
+}
+
+// the leaves in a flat list, cloned
+pub fn fringe(tree: &List) -> List {
+    leaves(tree).map(|leaf| leaf.dyn_clone()).collect()
+}
+
+// reverses every level, relinking the pairs instead of copying them.
+// panics on an improper list
+pub fn deep_reverse(tree: List) -> List {
+    let mut reversed = tree.reverse();
+    for item in reversed.iter_mut() {
+        if let Some(subtree) = item.cast_mut::<List>() {
+            *subtree = deep_reverse(subtree.take());
+        }
+    }
+    reversed
+}
+
+// same shape, and leaf_eq holds for every pair of leaves
+pub fn deep_equal(
+    a: &List,
+    b: &List,
+    mut leaf_eq: impl FnMut(&dyn ConsAny, &dyn ConsAny) -> bool,
+) -> bool {
+    let mut stack = vec![(a.iter(), b.iter())];
+    while let Some((x, y)) = stack.last_mut() {
+        match (x.next(), y.next()) {
+            (Some(x), Some(y)) => match (x.cast_ref::<List>(), y.cast_ref::<List>()) {
+                (Some(x), Some(y)) => stack.push((x.iter(), y.iter())),
+                (None, None) if leaf_eq(x, y) => {}
+                _ => return false,
+            },
+            (None, None) => {
+                match (x.take_tail(), y.take_tail()) {
+                    (Some(x), Some(y)) if leaf_eq(x, y) => {}
+                    (None, None) => {}
+                    _ => return false,
+                }
+                stack.pop();
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+// same shape minus the leaves pred rejects, subtrees are kept even
+// when nothing is left in them
+pub fn tree_filter(tree: &List, mut pred: impl FnMut(&dyn ConsAny) -> bool) -> List {
+    fn filter(tree: &List, pred: &mut dyn FnMut(&dyn ConsAny) -> bool) -> List {
+        let mut iter = tree.iter();
+        let mut kept: List = iter
+            .by_ref()
+            .filter_map(|item| match item.cast_ref::<List>() {
+                Some(subtree) => Some(Box::new(filter(subtree, pred)) as Box<dyn ConsAny>),
+                None => pred(item).then(|| item.dyn_clone()),
+            })
+            .collect();
+        if let Some(tail) = iter.take_tail().filter(|tail| pred(*tail)) {
+            if !kept.is_null() {
+                kept.set_dotted_tail(tail.dyn_clone());
+            }
+        }
+        kept
+    }
+    filter(tree, &mut pred)
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{list, quote};
+
+    #[test]
+    fn map_and_fold() {
+        let tree = list!(1, list!(2, list!(3, 4), 5), list!(6, 7));
+        let squared = tree_map(&tree, |x| x.cast_ref::<i32>().unwrap().pow(2));
+        assert_eq!(squared.to_string(), "(1 (4 (9 16) 25) (36 49))");
+        let names = tree_map(&list!(1, list!("a"); 2.0), |x| format!("{:?}", x));
+        assert_eq!(
+            names,
+            list!("1".to_string(), list!("\"a\"".to_string()); "2.0".to_string())
+        );
+
+        let sum = tree_fold(&tree, 0, |acc, x| acc + x.cast_ref::<i32>().unwrap());
+        assert_eq!(sum, 28);
+        assert_eq!(count_leaves(&tree), 7);
+        assert_eq!(count_leaves(&list!(list!(), 1; 2)), 2);
+        assert_eq!(depth(&tree), 3);
+        assert_eq!(depth(&List::nil()), 0);
+        assert_eq!(depth(&list!(list!(), 1)), 1);
+    }
+
+    #[test]
+    fn reshape() {
+        let tree = quote!((a (b (c d)) e));
+        assert_eq!(fringe(&tree), quote!((a b c d e)));
+        assert_eq!(deep_reverse(tree.clone()), quote!((e ((d c) b) a)));
+
+        let tree = list!(1, list!("x", 2), list!("y"); 3);
+        let ints = tree_filter(&tree, |x| x.cast_ref::<i32>().is_some());
+        assert_eq!(ints.to_string(), "(1 (2) () . 3)");
+        assert_eq!(tree_filter(&tree, |_| false), list!(list!(), list!()));
+    }
+
+    #[test]
+    fn compare_trees() {
+        let a = list!(1.0, list!(2.0; 3.0));
+        let b = list!(1.0000001, list!(2.0; 3.0));
+        let close = |x: &dyn ConsAny, y: &dyn ConsAny| {
+            let (x, y) = (x.cast_ref::<f64>().unwrap(), y.cast_ref::<f64>().unwrap());
+            (x - y).abs() < 1e-6
+        };
+        assert!(deep_equal(&a, &b, close));
+        assert!(!deep_equal(&a, &b, |x, y| x == y));
+        assert!(!deep_equal(&a, &list!(1.0, list!(2.0)), close));
+        assert!(!deep_equal(&a, &list!(1.0, 2.0), close));
+        assert!(deep_equal(&List::nil(), &List::nil(), close));
+    }
+
+    #[test]
+    fn walk_orders() {
+        let tree = List::parse("(1 (2 (3) . 4) () 5)").unwrap();
+        let show = |items: Vec<&dyn ConsAny>| {
+            let items = items.into_iter().map(|x| x.dyn_clone());
+            items.collect::<List>().to_string()
+        };
+        assert_eq!(
+            show(pre_order(&tree).collect()),
+            "(1 (2 (3) . 4) 2 (3) 3 4 () 5)"
+        );
+        assert_eq!(
+            show(post_order(&tree).collect()),
+            "(1 2 3 (3) 4 (2 (3) . 4) () 5)"
+        );
+        assert_eq!(show(leaves(&tree).collect()), "(1 2 3 4 5)");
+
+        let depths: Vec<_> = leaves(&tree).with_depth().map(|(d, _)| d).collect();
+        assert_eq!(depths, [1, 2, 3, 2, 1]);
+        let depths: Vec<_> = post_order(&tree).with_depth().map(|(d, _)| d).collect();
+        assert_eq!(depths, [1, 2, 3, 2, 2, 1, 1, 1]);
+        assert_eq!(pre_order(&List::nil()).count(), 0);
+    }
+
+    #[test]
+    fn test_same_fringe() {
+        let a = List::parse("(1 (2 3) ((4)) 5)").unwrap();
+        let b = List::parse("((1 2) 3 4 (5))").unwrap();
+        assert!(same_fringe(&a, &b));
+        assert!(!same_fringe(&a, &List::parse("(1 2 3 4)").unwrap()));
+        assert!(!same_fringe(&a, &List::parse("(1 2 3 4 5 6)").unwrap()));
+
+        // a balanced tree against a flat list, with leaves that count
+        // how often they are compared
+        fn build(low: i32, high: i32) -> List {
+            if high - low < 4 {
+                return (low..high).map(Counted).collect();
+            }
+            let mid = (low + high) / 2;
+            list!(build(low, mid), build(mid, high))
+        }
+        fn compared(a: &List, b: &List) -> (bool, usize) {
+            COMPARED.with(|count| count.set(0));
+            let same = same_fringe(a, b);
+            (same, COMPARED.with(Cell::get))
+        }
+        let n = 1 << 16;
+        let tree = build(0, n);
+        let flat: List = (0..n).map(Counted).collect();
+        assert_eq!(compared(&tree, &flat), (true, n as usize));
+        assert_eq!(leaves(&tree).with_depth().map(|(d, _)| d).max(), Some(16));
+        assert_eq!(depth(&tree), 16);
+
+        // the first leaf differs, nothing after it is compared
+        let other: List = (1..=n).map(Counted).collect();
+        assert_eq!(compared(&tree, &other), (false, 1));
+        let mut late = flat.clone();
+        *late
+            .iter_mut()
+            .nth(100)
+            .unwrap()
+            .cast_mut::<Counted>()
+            .unwrap() = Counted(-1);
+        assert_eq!(compared(&tree, &late), (false, 101));
+    }
+
+    thread_local! {
+        static COMPARED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Debug, Clone)]
+    struct Counted(i32);
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Self) -> bool {
+            COMPARED.with(|count| count.set(count.get() + 1));
+            self.0 == other.0
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use std::{
+    fmt::{self, Debug, Display, Write},
+    mem,
+};
+
+use num::rational::Rational64;
+
+use super::{cons::ConsAny, list::List, printer::write_atom, symbol::Symbol};
+
+// a scheme value as a plain enum, elements are matched on instead of
+// downcast from Box<dyn ConsAny>. A list is a chain of Pairs ending
+// in Nil, any other final cdr makes it an improper list
+//
+// match list.car() {
+//     Some(Value::Int(n)) => ...,
+//     Some(Value::Pair(pair)) => ...,
+//     _ => ...,
+// }
+#[derive(Default)]
+pub enum Value {
+    #[default]
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Rational(Rational64),
+    Str(String),
+    Symbol(Symbol),
+    Pair(Box<Pair>),
+    // any other atom, compared and cloned through ConsAny
+    Any(Box<dyn ConsAny>),
+}
+
+pub struct Pair {
+    pub car: Value,
+    pub cdr: Value,
+}
+
+impl Value {
+    // (cons car cdr)
+    pub fn cons(car: impl Into<Value>, cdr: Value) -> Self {
+        Value::Pair(Box::new(Pair {
+            car: car.into(),
+            cdr,
+        }))
+    }
+
+    // (list items...)
+    pub fn list<T: Into<Value>>(items: impl IntoIterator<Item = T>) -> Self {
+        items.into_iter().map(Into::into).collect()
+    }
+
+    pub fn any<T: ConsAny>(atom: T) -> Self {
+        Value::Any(Box::new(atom))
+    }
+
+    // (null? v)
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Nil)
+    }
+
+    // (pair? v)
+    pub fn is_pair(&self) -> bool {
+        matches!(self, Value::Pair(_))
+    }
+
+    pub fn car(&self) -> Option<&Value> {
+        match self {
+            Value::Pair(pair) => Some(&pair.car),
+            _ => None,
+        }
+    }
+
+    pub fn cdr(&self) -> Option<&Value> {
+        match self {
+            Value::Pair(pair) => Some(&pair.cdr),
+            _ => None,
+        }
+    }
+
+    pub fn car_mut(&mut self) -> Option<&mut Value> {
+        match self {
+            Value::Pair(pair) => Some(&mut pair.car),
+            _ => None,
+        }
+    }
+
+    pub fn cdr_mut(&mut self) -> Option<&mut Value> {
+        match self {
+            Value::Pair(pair) => Some(&mut pair.cdr),
+            _ => None,
+        }
+    }
+
+    // number of pairs in the cdr chain
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.is_pair()
+    }
+
+    // the cars of the cdr chain, a dotted tail is not yielded
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { next: self }
+    }
+
+    // the final cdr that is not a pair, Nil for a proper list
+    pub fn last_cdr(&self) -> &Value {
+        let mut value = self;
+        while let Value::Pair(pair) = value {
+            value = &pair.cdr;
+        }
+        value
+    }
+
+    // builds (items... . tail) from the back
+    fn from_items(items: Vec<Value>, tail: Value) -> Self {
+        items
+            .into_iter()
+            .rev()
+            .fold(tail, |cdr, car| Value::cons(car, cdr))
+    }
+}
+
+// pairs drop their cdr chain in a loop, like Cons does
+impl Drop for Pair {
+    fn drop(&mut self) {
+        let mut next = mem::take(&mut self.cdr);
+        while let Value::Pair(mut pair) = next {
+            next = mem::take(&mut pair.cdr);
+        }
+    }
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        match self {
+            Value::Nil => Value::Nil,
+            Value::Bool(b) => Value::Bool(*b),
+            Value::Int(n) => Value::Int(*n),
+            Value::Float(n) => Value::Float(*n),
+            Value::Rational(n) => Value::Rational(*n),
+            Value::Str(s) => Value::Str(s.clone()),
+            Value::Symbol(s) => Value::Symbol(*s),
+            Value::Any(atom) => Value::Any(atom.clone()),
+            Value::Pair(_) => {
+                let items = self.iter().cloned().collect();
+                Value::from_items(items, self.last_cdr().clone())
+            }
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Value) -> bool {
+        let (mut a, mut b) = (self, other);
+        loop {
+            return match (a, b) {
+                (Value::Pair(x), Value::Pair(y)) => {
+                    if x.car != y.car {
+                        return false;
+                    }
+                    (a, b) = (&x.cdr, &y.cdr);
+                    continue;
+                }
+                (Value::Nil, Value::Nil) => true,
+                (Value::Bool(x), Value::Bool(y)) => x == y,
+                (Value::Int(x), Value::Int(y)) => x == y,
+                (Value::Float(x), Value::Float(y)) => x == y,
+                (Value::Rational(x), Value::Rational(y)) => x == y,
+                (Value::Str(x), Value::Str(y)) => x == y,
+                (Value::Symbol(x), Value::Symbol(y)) => x == y,
+                (Value::Any(x), Value::Any(y)) => (**x).dyn_eq(&**y),
+                _ => false,
+            };
+        }
+    }
+}
+
+impl FromIterator<Value> for Value {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Value::from_items(iter.into_iter().collect(), Value::Nil)
+    }
+}
+
+impl<'a> IntoIterator for &'a Value {
+    type IntoIter = Iter<'a>;
+    type Item = &'a Value;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a> {
+    next: &'a Value,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Value;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next {
+            Value::Pair(pair) => {
+                self.next = &pair.cdr;
+                Some(&pair.car)
+            }
+            _ => None,
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(n: $t) -> Self {
+                    Value::Int(i64::from(n))
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Float(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<Rational64> for Value {
+    fn from(n: Rational64) -> Self {
+        Value::Rational(n)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl From<Symbol> for Value {
+    fn from(s: Symbol) -> Self {
+        Value::Symbol(s)
+    }
+}
+
+// atoms of a known type become their variant, nested Lists become
+// nested lists and anything else ends up in Value::Any
+impl From<&List> for Value {
+    fn from(list: &List) -> Self {
+        let items = list.iter().map(from_atom).collect();
+        Value::from_items(items, list.dotted_tail().map_or(Value::Nil, from_atom))
+    }
+}
+
+fn from_atom(atom: &dyn ConsAny) -> Value {
+    if let Some(list) = atom.cast_ref::<List>() {
+        return Value::from(list);
+    }
+    macro_rules! convert {
+        ($($t:ty),*) => {
+            $(
+                if let Some(x) = atom.cast_ref::<$t>() {
+                    return Value::from(x.clone());
+                }
+            )*
+        };
+    }
+    convert!(i8, i16, i32, i64, u8, u16, u32, f64, bool, Rational64, String, Symbol);
+    if let Some(s) = atom.cast_ref::<&'static str>() {
+        return Value::from(*s);
+    }
+    if let Some(value) = atom.cast_ref::<Value>() {
+        return value.clone();
+    }
+    Value::Any(atom.dyn_clone())
+}
+
+// same notation as List, atoms go through the List printer
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Nil => f.write_str("()"),
+            Value::Bool(b) => write_atom(f, b),
+            Value::Int(n) => write_atom(f, n),
+            Value::Float(n) => write_atom(f, n),
+            Value::Rational(n) => write!(f, "{}", n),
+            Value::Str(s) => write_atom(f, s),
+            Value::Symbol(s) => write_atom(f, s),
+            Value::Any(atom) => write_atom(f, &**atom),
+            Value::Pair(_) => {
+                f.write_char('(')?;
+                for (idx, item) in self.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_char(' ')?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                match self.last_cdr() {
+                    Value::Nil => {}
+                    tail => write!(f, " . {}", tail)?,
+                }
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+impl Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::list;
+
+    #[test]
+    fn match_values() {
+        let l = Value::list([Value::from(1), Value::list([2.5, 3.0]), Value::from("s")]);
+        assert_eq!(l.len(), 3);
+        let mut sum = 0.0;
+        for item in &l {
+            match item {
+                Value::Int(n) => sum += *n as f64,
+                Value::Pair(_) => {
+                    sum += item
+                        .iter()
+                        .map(|x| match x {
+                            Value::Float(n) => *n,
+                            _ => 0.0,
+                        })
+                        .sum::<f64>()
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(sum, 6.5);
+        assert_eq!(l.to_string(), "(1 (2.5 3.0) \"s\")");
+
+        let pair = Value::cons(Rational64::new(1, 2), Value::from(Symbol::new("x")));
+        assert_eq!(pair.to_string(), "(1/2 . x)");
+        assert!(pair.last_cdr().car().is_none());
+        assert_eq!(Value::Nil.to_string(), "()");
+        assert!(Value::list(Vec::<Value>::new()).is_null());
+    }
+
+    #[test]
+    fn from_list() {
+        let l = list!(1, list!(2i64, true), Symbol::new("a"), 'c'; 4.0);
+        let v = Value::from(&l);
+        assert_eq!(v.to_string(), l.to_string());
+        assert_eq!(v.car(), Some(&Value::Int(1)));
+        assert!(matches!(v.iter().nth(3), Some(Value::Any(_))));
+        assert_eq!(v.last_cdr(), &Value::Float(4.0));
+        assert_eq!(v.clone(), v);
+        assert_ne!(v, Value::from(&list!(1)));
+    }
+
+    #[test]
+    fn long_value_list() {
+        let l: Value = (0..1_000_000).map(Value::from).collect();
+        assert_eq!(l.len(), 1_000_000);
+        let copy = l.clone();
+        assert_eq!(copy, l);
+    }
+}